@@ -0,0 +1,240 @@
+mod movement;
+mod soundscape;
+mod surface;
+
+use std::collections::HashMap;
+
+use bevy::{
+    audio::{SpatialScale, Volume},
+    prelude::*,
+};
+use bevy_fps_controller::controller::*;
+use movement::MovementAudioPlugin;
+use serde::{Deserialize, Serialize};
+use soundscape::SoundscapePlugin;
+use surface::SurfacePlugin;
+
+use crate::prelude::*;
+
+/// Scales world units for the distance attenuation, sounds start to fall off about 10 units away.
+const SPATIAL_SCALE: f32 = 0.1;
+
+pub struct AudioPlugin;
+
+impl Plugin for AudioPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_plugins((MovementAudioPlugin, SoundscapePlugin, SurfacePlugin))
+            .add_observer(mix_sound)
+            .add_systems(Startup, setup)
+            .add_systems(
+                Update,
+                (
+                    shatter_sound,
+                    mix_playing_sounds.run_if(resource_changed::<Settings>),
+                ),
+            )
+            .add_systems(Update, load_level_sounds.run_if(resource_added::<Levels>))
+            .add_systems(Update, dive_sound.run_if(in_state(AppState::InGame)));
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AudioBus {
+    Master,
+    Music,
+    Ambient,
+    Sfx,
+    Ui,
+}
+
+impl AudioBus {
+    pub fn name(&self) -> &'static str {
+        match self {
+            AudioBus::Master => "Master",
+            AudioBus::Music => "Music",
+            AudioBus::Ambient => "Ambient",
+            AudioBus::Sfx => "SFX",
+            AudioBus::Ui => "UI",
+        }
+    }
+}
+
+/// Volume of each [`AudioBus`], every bus also goes through [`AudioBus::Master`].
+#[derive(Serialize, Deserialize, Debug, Clone, Copy)]
+#[serde(default)]
+pub struct AudioMix {
+    pub master: f32,
+    pub music: f32,
+    pub ambient: f32,
+    pub sfx: f32,
+    pub ui: f32,
+}
+
+impl Default for AudioMix {
+    fn default() -> Self {
+        Self {
+            master: 1.,
+            music: 1.,
+            ambient: 1.,
+            sfx: 1.,
+            ui: 1.,
+        }
+    }
+}
+
+impl AudioMix {
+    pub fn get(&self, bus: AudioBus) -> f32 {
+        match bus {
+            AudioBus::Master => self.master,
+            AudioBus::Music => self.music,
+            AudioBus::Ambient => self.ambient,
+            AudioBus::Sfx => self.sfx,
+            AudioBus::Ui => self.ui,
+        }
+    }
+
+    pub fn get_mut(&mut self, bus: AudioBus) -> &mut f32 {
+        match bus {
+            AudioBus::Master => &mut self.master,
+            AudioBus::Music => &mut self.music,
+            AudioBus::Ambient => &mut self.ambient,
+            AudioBus::Sfx => &mut self.sfx,
+            AudioBus::Ui => &mut self.ui,
+        }
+    }
+
+    pub fn volume(&self, sound: &BusSound) -> Volume {
+        let bus = match sound.bus {
+            AudioBus::Master => 1.,
+            bus => self.get(bus),
+        };
+
+        Volume::Linear(sound.volume * bus * self.master)
+    }
+}
+
+/// Tags an [`AudioPlayer`] with its [`AudioBus`], `volume` is relative to the bus.
+#[derive(Component, Debug, Clone, Copy)]
+pub struct BusSound {
+    pub bus: AudioBus,
+    pub volume: f32,
+}
+
+impl BusSound {
+    pub fn new(bus: AudioBus, volume: f32) -> Self {
+        Self { bus, volume }
+    }
+}
+
+/// Sets the starting volume, before the sink is created.
+fn mix_sound(
+    add: On<Add, BusSound>,
+    settings: Res<Settings>,
+    mut q_sound: Query<(&BusSound, &mut PlaybackSettings)>,
+) {
+    if let Ok((sound, mut playback)) = q_sound.get_mut(add.entity) {
+        playback.volume = settings.mix.volume(sound);
+    }
+}
+
+fn mix_playing_sounds(
+    settings: Res<Settings>,
+    mut q_sink: Query<(&BusSound, &mut AudioSink)>,
+    mut q_spatial_sink: Query<(&BusSound, &mut SpatialAudioSink)>,
+) {
+    for (sound, mut sink) in &mut q_sink {
+        sink.set_volume(settings.mix.volume(sound));
+    }
+
+    for (sound, mut sink) in &mut q_spatial_sink {
+        sink.set_volume(settings.mix.volume(sound));
+    }
+}
+
+/// Plays once at the [`Transform`] of the sound, attenuated by the distance to the [`SpatialListener`].
+pub fn spatial_playback() -> PlaybackSettings {
+    PlaybackSettings::DESPAWN
+        .with_spatial(true)
+        .with_spatial_scale(SpatialScale::new(SPATIAL_SCALE))
+}
+
+/// Sounds used by the game itself, keyed by name. Level sounds are added under their path.
+const SOUNDS: [(&str, &str); 5] = [
+    ("dive", "dive_sound/dive.mp3"),
+    ("boost", "boost_sound/ui-sound-270349.mp3"),
+    ("glass", "glass_sound/glass-break.mp3"),
+    ("shatter", "glass_sound/glass-shatter.mp3"),
+    ("land", "land_sound/land.mp3"),
+];
+
+#[derive(Resource, Debug, Default)]
+pub struct SoundBank(HashMap<String, Handle<AudioSource>>);
+
+impl SoundBank {
+    fn load(&mut self, key: &str, path: &str, assets: &AssetServer, loading: &mut AssetsLoading) {
+        if self.0.contains_key(key) {
+            return;
+        }
+
+        let handle = assets.load(path.to_owned());
+        loading.0.push(handle.clone().into());
+        self.0.insert(key.to_owned(), handle);
+    }
+
+    pub fn get(&self, key: &str) -> Handle<AudioSource> {
+        self.0.get(key).cloned().unwrap_or_else(|| {
+            warn!("The sound {key} is not in the sound bank!");
+            default()
+        })
+    }
+}
+
+fn setup(assets: Res<AssetServer>, mut cmd: Commands, mut loading: ResMut<AssetsLoading>) {
+    let mut bank = SoundBank::default();
+
+    for (key, path) in SOUNDS {
+        bank.load(key, path, &assets, &mut loading);
+    }
+
+    cmd.insert_resource(bank);
+}
+
+fn load_level_sounds(
+    assets: Res<AssetServer>,
+    levels: Res<Levels>,
+    mut bank: ResMut<SoundBank>,
+    mut loading: ResMut<AssetsLoading>,
+) {
+    for sound in levels.iter().flat_map(|x| x.sounds()) {
+        bank.load(&sound.sound, &sound.sound, &assets, &mut loading);
+    }
+}
+
+/// Plays when the player ends up in the water, through falling out of bounds or a kill zone.
+fn dive_sound(
+    mut cmd: Commands,
+    mut er: MessageReader<Respawn<LogicalPlayer>>,
+    bank: Res<SoundBank>,
+) {
+    for e in er.read() {
+        if !matches!(e.cause, RespawnCause::Fell | RespawnCause::KillZone) {
+            continue;
+        }
+
+        cmd.spawn((
+            AudioPlayer::new(bank.get("dive")),
+            PlaybackSettings::DESPAWN,
+            BusSound::new(AudioBus::Sfx, 0.15),
+        ));
+    }
+}
+
+fn shatter_sound(mut cmd: Commands, bank: Res<SoundBank>, mut er: MessageReader<SpawnLevel>) {
+    for _ in er.read() {
+        cmd.spawn((
+            AudioPlayer::new(bank.get("shatter")),
+            PlaybackSettings::DESPAWN,
+            BusSound::new(AudioBus::Sfx, 0.2),
+        ));
+    }
+}
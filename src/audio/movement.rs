@@ -0,0 +1,254 @@
+use std::{f32::consts::FRAC_PI_2, mem, time::Duration};
+
+use avian3d::prelude::*;
+use bevy::{
+    audio::{AddAudioSource, Decodable, Source},
+    prelude::*,
+};
+use bevy_fps_controller::controller::*;
+
+use super::surface::{GroundSurface, ground_surface, surface_sounds};
+use crate::prelude::*;
+
+/// Speed at which the wind starts to blow.
+const WIND_MIN_SPEED: f32 = 10.;
+/// Speed at which the rush starts to take over from the wind.
+const RUSH_MIN_SPEED: f32 = 20.;
+const WIND_MAX_SPEED: f32 = 40.;
+const WIND_VOLUME: f32 = 0.3;
+const RUSH_VOLUME: f32 = 0.2;
+/// Share of the wind that is still heard on the ground.
+const GROUNDED_WIND: f32 = 0.5;
+/// How fast the loops follow the speed, per second.
+const WIND_SMOOTHING: f32 = 8.;
+
+/// Ticks on the ground that still count as a bunny hop.
+const BHOP_GROUND_TICKS: u8 = 2;
+/// Share of the landing speed that has to be kept for a bunny hop.
+const BHOP_SPEED_KEPT: f32 = 0.95;
+/// Slower landings are neither bunny hops nor lose any speed worth mentioning.
+const BHOP_MIN_SPEED: f32 = 12.;
+
+const NOISE_SAMPLE_RATE: u32 = 44_100;
+
+/// Adaptive layer following the player's movement: wind and rush loops generated from filtered
+/// noise, and landing sounds telling bunny hops apart from landings that lost speed.
+pub(super) struct MovementAudioPlugin;
+
+impl Plugin for MovementAudioPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_audio_source::<Noise>()
+            .add_message::<Landing>()
+            .add_systems(Startup, setup)
+            .add_systems(OnEnter(AppState::InGame), spawn_wind)
+            .add_systems(OnExit(AppState::InGame), cleanup::<WindLayer>)
+            .add_systems(FixedUpdate, detect_landing.in_set(GameplaySet))
+            .add_systems(
+                Update,
+                (land_sound.after(ground_surface), wind.in_set(GameplaySet)),
+            );
+    }
+}
+
+/// Endless white noise through a one-pole low-pass filter, lower cutoffs sound darker.
+#[derive(Asset, TypePath)]
+pub struct Noise {
+    /// Share of each new sample that passes the filter, between 0 and 1.
+    pub(super) cutoff: f32,
+}
+
+pub struct NoiseDecoder {
+    state: u32,
+    low: f32,
+    cutoff: f32,
+    gain: f32,
+}
+
+impl Iterator for NoiseDecoder {
+    type Item = f32;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        // Xorshift, plenty random for noise.
+        self.state ^= self.state << 13;
+        self.state ^= self.state >> 17;
+        self.state ^= self.state << 5;
+
+        let white = self.state as f32 / u32::MAX as f32 * 2. - 1.;
+        self.low += (white - self.low) * self.cutoff;
+
+        Some(self.low * self.gain)
+    }
+}
+
+impl Source for NoiseDecoder {
+    fn current_frame_len(&self) -> Option<usize> {
+        None
+    }
+
+    fn channels(&self) -> u16 {
+        1
+    }
+
+    fn sample_rate(&self) -> u32 {
+        NOISE_SAMPLE_RATE
+    }
+
+    fn total_duration(&self) -> Option<Duration> {
+        None
+    }
+}
+
+impl Decodable for Noise {
+    type DecoderItem = <NoiseDecoder as Iterator>::Item;
+    type Decoder = NoiseDecoder;
+
+    fn decoder(&self) -> Self::Decoder {
+        NoiseDecoder {
+            state: 0x9E37_79B9,
+            low: 0.,
+            cutoff: self.cutoff,
+            // Evens out the loudness of the cutoffs, the filter takes the edge off the peaks.
+            gain: 0.25 / (self.cutoff / (2. - self.cutoff) / 3.).sqrt(),
+        }
+    }
+}
+
+#[derive(Resource)]
+struct WindSounds {
+    wind: Handle<Noise>,
+    rush: Handle<Noise>,
+}
+
+#[derive(Component, Debug, Clone, Copy)]
+enum WindLayer {
+    Wind,
+    Rush,
+}
+
+fn setup(mut cmd: Commands, mut noises: ResMut<Assets<Noise>>) {
+    cmd.insert_resource(WindSounds {
+        wind: noises.add(Noise { cutoff: 0.02 }),
+        rush: noises.add(Noise { cutoff: 0.25 }),
+    });
+}
+
+fn spawn_wind(mut cmd: Commands, sounds: Res<WindSounds>) {
+    for (layer, noise) in [
+        (WindLayer::Wind, &sounds.wind),
+        (WindLayer::Rush, &sounds.rush),
+    ] {
+        cmd.spawn((
+            layer,
+            AudioPlayer(noise.clone()),
+            PlaybackSettings::LOOP,
+            BusSound::new(AudioBus::Ambient, 0.),
+        ));
+    }
+}
+
+/// Crossfades from wind to rush with speed, raising the pitch along the way.
+fn wind(
+    time: Res<Time>,
+    settings: Res<Settings>,
+    q_player: Query<(&FpsController, &LinearVelocity), With<LogicalPlayer>>,
+    mut q_layer: Query<(&WindLayer, &mut BusSound, &mut AudioSink)>,
+) {
+    let Ok((controller, velocity)) = q_player.single() else {
+        return;
+    };
+
+    let speed = velocity.0.length();
+    let intensity = ((speed - WIND_MIN_SPEED) / (WIND_MAX_SPEED - WIND_MIN_SPEED)).clamp(0., 1.);
+    let rush = ((speed - RUSH_MIN_SPEED) / (WIND_MAX_SPEED - RUSH_MIN_SPEED)).clamp(0., 1.);
+    let airborne = match controller.ground_tick {
+        0 => 1.,
+        _ => GROUNDED_WIND,
+    };
+    let smoothing = 1. - (-WIND_SMOOTHING * time.delta_secs()).exp();
+
+    for (layer, mut sound, mut sink) in &mut q_layer {
+        // Equal power crossfade, so the loudness doesn't dip in between.
+        let (volume, pitch) = match layer {
+            WindLayer::Wind => (
+                (rush * FRAC_PI_2).cos() * WIND_VOLUME,
+                0.8 + intensity * 0.4,
+            ),
+            WindLayer::Rush => ((rush * FRAC_PI_2).sin() * RUSH_VOLUME, 0.9 + rush * 0.5),
+        };
+
+        sound.volume += (volume * intensity * airborne - sound.volume) * smoothing;
+        sink.set_volume(settings.mix.volume(&sound));
+        sink.set_speed(pitch);
+    }
+}
+
+/// Touching down, told apart every fixed tick so no ground tick is skipped.
+#[derive(Message, Debug, Clone, Copy)]
+enum Landing {
+    Land,
+    /// Jumped again right away.
+    BunnyHop,
+    /// Stayed on the ground long enough for the friction to kick in.
+    LostSpeed,
+}
+
+/// Ground ticks of the last tick and horizontal speed of the last landing.
+#[derive(Default)]
+struct LandingState {
+    ground_tick: u8,
+    speed: f32,
+}
+
+fn detect_landing(
+    q_player: Query<(&FpsController, &LinearVelocity), With<LogicalPlayer>>,
+    mut state: Local<LandingState>,
+    mut ew: MessageWriter<Landing>,
+) {
+    let Ok((controller, velocity)) = q_player.single() else {
+        return;
+    };
+
+    let speed = velocity.0.with_y(0.).length();
+    let previous = mem::replace(&mut state.ground_tick, controller.ground_tick);
+    let kept_speed = speed >= state.speed * BHOP_SPEED_KEPT;
+
+    let landing = match (previous, controller.ground_tick) {
+        (0, 1..) => {
+            state.speed = speed;
+            Landing::Land
+        }
+        (1..=BHOP_GROUND_TICKS, 0) if state.speed >= BHOP_MIN_SPEED && kept_speed => {
+            Landing::BunnyHop
+        }
+        (1..=BHOP_GROUND_TICKS, ground_tick)
+            if ground_tick > BHOP_GROUND_TICKS && state.speed >= BHOP_MIN_SPEED && !kept_speed =>
+        {
+            Landing::LostSpeed
+        }
+        _ => return,
+    };
+
+    ew.write(landing);
+}
+
+fn land_sound(
+    mut cmd: Commands,
+    mut er: MessageReader<Landing>,
+    bank: Res<SoundBank>,
+    surface: Res<GroundSurface>,
+) {
+    for landing in er.read() {
+        let (volume, pitch) = match landing {
+            Landing::Land => (0.2, 1.),
+            Landing::BunnyHop => (0.15, 1.5),
+            Landing::LostSpeed => (0.25, 0.6),
+        };
+
+        let sounds = surface_sounds(surface.0);
+        cmd.spawn((
+            AudioPlayer::new(bank.get(sounds.key)),
+            PlaybackSettings::DESPAWN.with_speed(sounds.pitch * pitch),
+            BusSound::new(AudioBus::Sfx, sounds.volume * volume),
+        ));
+    }
+}
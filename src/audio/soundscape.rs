@@ -0,0 +1,158 @@
+use bevy::{audio::Volume, prelude::*};
+
+use crate::prelude::*;
+
+/// Seconds a crossfade between levels takes.
+const CROSSFADE_SECS: f32 = 2.;
+
+/// Music and ambient loops declared per level in the manifest, crossfaded on level changes.
+pub(super) struct SoundscapePlugin;
+
+impl Plugin for SoundscapePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(OnEnter(AppState::InGame), start_soundscape)
+            .add_systems(OnExit(AppState::InGame), cleanup::<SoundscapeSound>)
+            .add_systems(
+                Update,
+                (
+                    change_soundscape.run_if(in_state(AppState::InGame)),
+                    fade_soundscape.after(super::mix_playing_sounds),
+                )
+                    .chain(),
+            );
+    }
+}
+
+/// Loop of the current soundscape, fading towards `volume` and despawning once silent when
+/// it isn't part of the level anymore.
+#[derive(Component, Debug)]
+struct SoundscapeSound {
+    key: String,
+    volume: f32,
+    fading_out: bool,
+}
+
+fn start_soundscape(
+    mut cmd: Commands,
+    levels: Res<Levels>,
+    current_level: Res<CurrentLevel>,
+    bank: Res<SoundBank>,
+    mut q_sound: Query<(&mut SoundscapeSound, &BusSound)>,
+) {
+    crossfade(
+        &mut cmd,
+        levels.get(current_level.get()),
+        &bank,
+        &mut q_sound,
+    );
+}
+
+fn change_soundscape(
+    mut cmd: Commands,
+    mut er: MessageReader<SpawnLevel>,
+    levels: Res<Levels>,
+    bank: Res<SoundBank>,
+    mut q_sound: Query<(&mut SoundscapeSound, &BusSound)>,
+) {
+    for SpawnLevel(level) in er.read() {
+        crossfade(&mut cmd, levels.get(*level), &bank, &mut q_sound);
+    }
+}
+
+/// Keeps the loops `level` shares with the playing soundscape and fades the others in or out.
+fn crossfade(
+    cmd: &mut Commands,
+    level: &LevelInfo,
+    bank: &SoundBank,
+    q_sound: &mut Query<(&mut SoundscapeSound, &BusSound)>,
+) {
+    let sounds = level
+        .music
+        .iter()
+        .map(|x| (x, AudioBus::Music))
+        .chain(level.ambient.iter().map(|x| (x, AudioBus::Ambient)))
+        .collect::<Vec<_>>();
+
+    for (mut playing, sound) in q_sound.iter_mut() {
+        let next = sounds
+            .iter()
+            .find(|(x, bus)| x.sound == playing.key && *bus == sound.bus);
+
+        match next {
+            Some((x, _)) => {
+                playing.volume = x.volume;
+                playing.fading_out = false;
+            }
+            None => playing.fading_out = true,
+        }
+    }
+
+    for (sound, bus) in sounds {
+        let is_playing = q_sound
+            .iter()
+            .any(|(playing, x)| playing.key == sound.sound && x.bus == bus);
+
+        if is_playing {
+            continue;
+        }
+
+        cmd.spawn((
+            SoundscapeSound {
+                key: sound.sound.clone(),
+                volume: sound.volume,
+                fading_out: false,
+            },
+            AudioPlayer::new(bank.get(&sound.sound)),
+            PlaybackSettings::LOOP,
+            BusSound::new(bus, 0.),
+        ));
+    }
+}
+
+/// Fades the loops and ducks the music while paused, stopping it if the paused volume is 0.
+fn fade_soundscape(
+    mut cmd: Commands,
+    time: Res<Time<Real>>,
+    settings: Res<Settings>,
+    paused: Res<State<PausedState>>,
+    mut q_sound: Query<(
+        Entity,
+        &SoundscapeSound,
+        &mut BusSound,
+        Option<&mut AudioSink>,
+    )>,
+) {
+    let step = time.delta_secs() / CROSSFADE_SECS;
+    let is_paused = *paused.get() == PausedState::Paused;
+
+    for (entity, playing, mut sound, sink) in &mut q_sound {
+        let target = match playing.fading_out {
+            true => 0.,
+            false => playing.volume,
+        };
+        sound.volume += (target - sound.volume).clamp(-step, step);
+
+        if playing.fading_out && sound.volume <= 0. {
+            cmd.entity(entity).despawn();
+            continue;
+        }
+
+        let Some(mut sink) = sink else {
+            continue;
+        };
+
+        let duck = match (sound.bus, is_paused) {
+            (AudioBus::Music, true) => settings.paused_music_volume,
+            _ => 1.,
+        };
+
+        match duck > 0. {
+            true => sink.play(),
+            false => sink.pause(),
+        }
+
+        sink.set_volume(Volume::Linear(
+            settings.mix.volume(&sound).to_linear() * duck,
+        ));
+    }
+}
@@ -0,0 +1,191 @@
+use avian3d::prelude::*;
+use bevy::prelude::*;
+use bevy_fps_controller::controller::*;
+
+use super::movement::Noise;
+use crate::prelude::*;
+
+/// Horizontal distance between two footsteps.
+const STEP_DISTANCE: f32 = 3.;
+/// Slower movement is just shuffling around.
+const STEP_MIN_SPEED: f32 = 1.;
+/// Faster than running on the ground means the friction is scrubbing off speed.
+const SLIDE_MIN_SPEED: f32 = 15.;
+const SLIDE_MAX_SPEED: f32 = 40.;
+const SLIDE_VOLUME: f32 = 0.3;
+/// How fast the slide follows the speed, per second.
+const SLIDE_SMOOTHING: f32 = 12.;
+/// Contacts steeper than this don't count as ground.
+const GROUND_MIN_NORMAL_Y: f32 = 0.7;
+
+/// Footsteps and slides of the player, sounding like the material of the ground it stands on.
+pub(super) struct SurfacePlugin;
+
+impl Plugin for SurfacePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<GroundSurface>()
+            .add_systems(Startup, setup)
+            .add_systems(OnEnter(AppState::InGame), spawn_slide)
+            .add_systems(
+                OnExit(AppState::InGame),
+                (cleanup::<SlideSound>, reset_surface),
+            )
+            .add_systems(
+                Update,
+                (ground_surface, (footstep_sound, slide_sound))
+                    .chain()
+                    .in_set(GameplaySet),
+            );
+    }
+}
+
+/// Material of the ground the player last stood on.
+#[derive(Resource, Debug, Default)]
+pub(super) struct GroundSurface(pub(super) SurfaceMaterial);
+
+/// How a material sounds. The samples are shared between materials and pitched to tell them apart.
+pub(super) struct SurfaceSounds {
+    pub(super) key: &'static str,
+    pub(super) volume: f32,
+    pub(super) pitch: f32,
+}
+
+pub(super) fn surface_sounds(material: SurfaceMaterial) -> SurfaceSounds {
+    let (key, volume, pitch) = match material {
+        SurfaceMaterial::Stone => ("land", 1., 1.),
+        SurfaceMaterial::Metal => ("land", 0.8, 1.6),
+        SurfaceMaterial::Glass => ("glass", 0.25, 1.8),
+        SurfaceMaterial::Wood => ("land", 1.2, 0.75),
+    };
+
+    SurfaceSounds { key, volume, pitch }
+}
+
+#[derive(Resource)]
+struct SlideNoise(Handle<Noise>);
+
+#[derive(Component)]
+struct SlideSound;
+
+fn setup(mut cmd: Commands, mut noises: ResMut<Assets<Noise>>) {
+    cmd.insert_resource(SlideNoise(noises.add(Noise { cutoff: 0.1 })));
+}
+
+fn spawn_slide(mut cmd: Commands, noise: Res<SlideNoise>) {
+    cmd.spawn((
+        SlideSound,
+        AudioPlayer(noise.0.clone()),
+        PlaybackSettings::LOOP,
+        BusSound::new(AudioBus::Sfx, 0.),
+    ));
+}
+
+fn reset_surface(mut surface: ResMut<GroundSurface>) {
+    *surface = default();
+}
+
+/// Finds the ground among the player's contacts, ground without a material sounds like stone.
+pub(super) fn ground_surface(
+    collisions: Collisions,
+    q_player: Query<(Entity, &FpsController), With<LogicalPlayer>>,
+    q_material: Query<&SurfaceMaterial>,
+    mut surface: ResMut<GroundSurface>,
+) {
+    let Ok((player, controller)) = q_player.single() else {
+        return;
+    };
+
+    if controller.ground_tick == 0 {
+        return;
+    }
+
+    let ground = collisions.collisions_with(player).find_map(|pair| {
+        // Normals point from the first collider to the second, flipped so they point at the player.
+        let (other, flip) = match pair.collider1 == player {
+            true => (pair.collider2, -1.),
+            false => (pair.collider1, 1.),
+        };
+
+        pair.manifolds
+            .iter()
+            .any(|x| x.normal.y * flip >= GROUND_MIN_NORMAL_Y)
+            .then_some(other)
+    });
+
+    if let Some(ground) = ground {
+        surface.0 = q_material.get(ground).copied().unwrap_or_default();
+    }
+}
+
+fn footstep_sound(
+    mut cmd: Commands,
+    time: Res<Time>,
+    bank: Res<SoundBank>,
+    surface: Res<GroundSurface>,
+    q_player: Query<(&FpsController, &LinearVelocity), With<LogicalPlayer>>,
+    mut distance: Local<f32>,
+) {
+    let Ok((controller, velocity)) = q_player.single() else {
+        return;
+    };
+
+    let speed = velocity.0.with_y(0.).length();
+
+    match (controller.ground_tick, speed) {
+        // The landing sound covers the first step.
+        (0, _) => {
+            *distance = 0.;
+            return;
+        }
+        // Starts walking off with a step.
+        (_, speed) if !(STEP_MIN_SPEED..SLIDE_MIN_SPEED).contains(&speed) => {
+            *distance = STEP_DISTANCE;
+            return;
+        }
+        _ => (),
+    }
+
+    *distance += speed * time.delta_secs();
+
+    if *distance < STEP_DISTANCE {
+        return;
+    }
+
+    *distance = 0.;
+
+    let sounds = surface_sounds(surface.0);
+    cmd.spawn((
+        AudioPlayer::new(bank.get(sounds.key)),
+        PlaybackSettings::DESPAWN.with_speed(sounds.pitch),
+        BusSound::new(AudioBus::Sfx, sounds.volume * 0.08),
+    ));
+}
+
+/// Fades the slide in with the speed the ground friction is scrubbing off.
+fn slide_sound(
+    time: Res<Time>,
+    settings: Res<Settings>,
+    surface: Res<GroundSurface>,
+    q_player: Query<(&FpsController, &LinearVelocity), With<LogicalPlayer>>,
+    mut q_slide: Query<(&mut BusSound, &mut AudioSink), With<SlideSound>>,
+) {
+    let Ok((controller, velocity)) = q_player.single() else {
+        return;
+    };
+
+    let speed = velocity.0.with_y(0.).length();
+    let intensity = match controller.ground_tick {
+        0 => 0.,
+        _ => ((speed - SLIDE_MIN_SPEED) / (SLIDE_MAX_SPEED - SLIDE_MIN_SPEED)).clamp(0., 1.),
+    };
+    let sounds = surface_sounds(surface.0);
+    let smoothing = 1. - (-SLIDE_SMOOTHING * time.delta_secs()).exp();
+
+    for (mut sound, mut sink) in &mut q_slide {
+        let volume = intensity.sqrt() * SLIDE_VOLUME * sounds.volume;
+
+        sound.volume += (volume - sound.volume) * smoothing;
+        sink.set_volume(settings.mix.volume(&sound));
+        sink.set_speed(sounds.pitch * (0.8 + intensity * 0.4));
+    }
+}
@@ -0,0 +1,392 @@
+use std::{collections::BTreeMap, fmt, mem};
+
+use bevy::{ecs::system::SystemParam, input::keyboard::NativeKeyCode, prelude::*};
+use bevy_fps_controller::controller::FpsController;
+use serde::{Deserialize, Serialize};
+
+use crate::storage;
+
+const BINDINGS_KEY: &str = "bindings.ron";
+
+/// Never pressed, for controller inputs without a plain key.
+const UNBOUND_KEY: KeyCode = KeyCode::Unidentified(NativeKeyCode::Unidentified);
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Action {
+    Forward,
+    Back,
+    Left,
+    Right,
+    Jump,
+    Crouch,
+    Sprint,
+    Fly,
+    FlyUp,
+    FlyDown,
+    ToggleAutoJump,
+    ResetCheckpoint,
+    ResetLevel,
+    Pause,
+    CaptureCursor,
+    Pickup,
+    Throw,
+    Drop,
+    PracticeSave,
+    PracticeTeleport,
+    PracticePrevious,
+    PracticeNext,
+    MenuUp,
+    MenuDown,
+    MenuLeft,
+    MenuRight,
+    MenuConfirm,
+}
+
+impl Action {
+    pub fn from_name(name: &str) -> Option<Self> {
+        ron::from_str(name).ok()
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BindingButton {
+    Key(KeyCode),
+    Mouse(MouseButton),
+    Gamepad(GamepadButton),
+}
+
+impl BindingButton {
+    /// Whether both are keys, mouse buttons or gamepad buttons.
+    pub fn same_device(&self, other: &Self) -> bool {
+        mem::discriminant(self) == mem::discriminant(other)
+    }
+}
+
+impl fmt::Display for BindingButton {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BindingButton::Key(key) => write!(f, "{key:?}"),
+            BindingButton::Mouse(button) => write!(f, "Mouse{button:?}"),
+            BindingButton::Gamepad(button) => write!(f, "Gamepad{button:?}"),
+        }
+    }
+}
+
+/// A [`BindingButton`], optionally only while a modifier key is held.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Binding {
+    pub modifier: Option<KeyCode>,
+    pub button: BindingButton,
+}
+
+impl Binding {
+    const fn key(key: KeyCode) -> Self {
+        Self {
+            modifier: None,
+            button: BindingButton::Key(key),
+        }
+    }
+
+    const fn mouse(button: MouseButton) -> Self {
+        Self {
+            modifier: None,
+            button: BindingButton::Mouse(button),
+        }
+    }
+
+    const fn gamepad(button: GamepadButton) -> Self {
+        Self {
+            modifier: None,
+            button: BindingButton::Gamepad(button),
+        }
+    }
+
+    const fn with_modifier(self, modifier: KeyCode) -> Self {
+        Self {
+            modifier: Some(modifier),
+            ..self
+        }
+    }
+
+    /// Parses bindings like `KeyR`, `ShiftLeft+KeyR`, `MouseLeft` or `GamepadSouth`.
+    pub fn parse(s: &str) -> Option<Self> {
+        let (modifier, button) = match s.split_once('+') {
+            Some((modifier, button)) => (Some(ron::from_str(modifier).ok()?), button),
+            None => (None, s),
+        };
+
+        let button = if let Some(button) = button.strip_prefix("Mouse") {
+            BindingButton::Mouse(ron::from_str(button).ok()?)
+        } else if let Some(button) = button.strip_prefix("Gamepad") {
+            BindingButton::Gamepad(ron::from_str(button).ok()?)
+        } else {
+            BindingButton::Key(ron::from_str(button).ok()?)
+        };
+
+        Some(Self { modifier, button })
+    }
+}
+
+impl fmt::Display for Binding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.modifier {
+            Some(modifier) => write!(f, "{modifier:?}+{}", self.button),
+            None => write!(f, "{}", self.button),
+        }
+    }
+}
+
+/// Bindings of each [`Action`], persisted across sessions.
+#[derive(Resource, Serialize, Deserialize, Debug, Clone)]
+pub struct Bindings(pub BTreeMap<Action, Vec<Binding>>);
+
+impl Default for Bindings {
+    fn default() -> Self {
+        use Action::*;
+
+        Self(BTreeMap::from([
+            (Forward, vec![Binding::key(KeyCode::KeyW)]),
+            (Back, vec![Binding::key(KeyCode::KeyS)]),
+            (Left, vec![Binding::key(KeyCode::KeyA)]),
+            (Right, vec![Binding::key(KeyCode::KeyD)]),
+            (
+                Jump,
+                vec![
+                    Binding::key(KeyCode::Space),
+                    Binding::gamepad(GamepadButton::South),
+                ],
+            ),
+            (
+                Crouch,
+                vec![
+                    Binding::key(KeyCode::ControlLeft),
+                    Binding::gamepad(GamepadButton::East),
+                ],
+            ),
+            (
+                Sprint,
+                vec![
+                    Binding::key(KeyCode::ShiftLeft),
+                    Binding::gamepad(GamepadButton::LeftThumb),
+                ],
+            ),
+            (Fly, vec![Binding::key(KeyCode::KeyF)]),
+            (FlyUp, vec![Binding::key(KeyCode::KeyQ)]),
+            (FlyDown, vec![Binding::key(KeyCode::KeyE)]),
+            (
+                ToggleAutoJump,
+                vec![
+                    Binding::key(KeyCode::Space).with_modifier(KeyCode::ShiftLeft),
+                    Binding::gamepad(GamepadButton::RightThumb),
+                ],
+            ),
+            (
+                ResetCheckpoint,
+                vec![
+                    Binding::key(KeyCode::KeyR),
+                    Binding::gamepad(GamepadButton::West),
+                ],
+            ),
+            (
+                ResetLevel,
+                vec![
+                    Binding::key(KeyCode::KeyR).with_modifier(KeyCode::ShiftLeft),
+                    Binding::gamepad(GamepadButton::North),
+                ],
+            ),
+            (
+                Pause,
+                vec![
+                    Binding::key(KeyCode::Escape),
+                    Binding::gamepad(GamepadButton::Start),
+                ],
+            ),
+            (CaptureCursor, vec![Binding::mouse(MouseButton::Left)]),
+            (
+                Pickup,
+                vec![
+                    Binding::mouse(MouseButton::Right),
+                    Binding::gamepad(GamepadButton::LeftTrigger2),
+                ],
+            ),
+            (
+                Throw,
+                vec![
+                    Binding::mouse(MouseButton::Left),
+                    Binding::gamepad(GamepadButton::RightTrigger2),
+                ],
+            ),
+            (
+                Drop,
+                vec![
+                    Binding::mouse(MouseButton::Right),
+                    Binding::gamepad(GamepadButton::LeftTrigger2),
+                ],
+            ),
+            (PracticeSave, vec![Binding::key(KeyCode::Digit1)]),
+            (PracticeTeleport, vec![Binding::key(KeyCode::Digit2)]),
+            (PracticePrevious, vec![Binding::key(KeyCode::Digit3)]),
+            (PracticeNext, vec![Binding::key(KeyCode::Digit4)]),
+            (
+                MenuUp,
+                vec![
+                    Binding::key(KeyCode::ArrowUp),
+                    Binding::gamepad(GamepadButton::DPadUp),
+                ],
+            ),
+            (
+                MenuDown,
+                vec![
+                    Binding::key(KeyCode::ArrowDown),
+                    Binding::gamepad(GamepadButton::DPadDown),
+                ],
+            ),
+            (
+                MenuLeft,
+                vec![
+                    Binding::key(KeyCode::ArrowLeft),
+                    Binding::gamepad(GamepadButton::DPadLeft),
+                ],
+            ),
+            (
+                MenuRight,
+                vec![
+                    Binding::key(KeyCode::ArrowRight),
+                    Binding::gamepad(GamepadButton::DPadRight),
+                ],
+            ),
+            (
+                MenuConfirm,
+                vec![
+                    Binding::key(KeyCode::Enter),
+                    Binding::gamepad(GamepadButton::South),
+                ],
+            ),
+        ]))
+    }
+}
+
+impl Bindings {
+    /// Saved bindings on top of the defaults, so new actions are bound too.
+    pub fn load() -> Self {
+        let mut bindings = Self::default();
+
+        let bytes = match storage::load_config(BINDINGS_KEY) {
+            Ok(Some(bytes)) => bytes,
+            Ok(None) => return bindings,
+            Err(e) => {
+                warn!("Failed to load bindings: {e}");
+                return bindings;
+            }
+        };
+
+        match ron::de::from_bytes::<Self>(&bytes) {
+            Ok(saved) => bindings.0.extend(saved.0),
+            Err(e) => warn!("Failed to parse bindings: {e}"),
+        }
+
+        bindings
+    }
+
+    pub fn save(&self) -> Result<(), BevyError> {
+        let ron = ron::ser::to_string_pretty(self, default())?;
+        storage::save_config(BINDINGS_KEY, ron.as_bytes())
+    }
+
+    pub fn get(&self, action: Action) -> &[Binding] {
+        self.0.get(&action).map_or(&[], |x| x.as_slice())
+    }
+
+    /// Replaces the binding of `action` on the same device, keeping the others.
+    pub fn bind(&mut self, action: Action, binding: Binding) {
+        let bindings = self.0.entry(action).or_default();
+        bindings.retain(|x| !x.button.same_device(&binding.button));
+        bindings.push(binding);
+    }
+
+    /// First plain key of `action`, as the fps controller only takes a single key per input. The
+    /// other bindings are handled by [`binding_input`](crate::input::binding_input).
+    fn key(&self, action: Action) -> Option<KeyCode> {
+        self.get(action).iter().find_map(|x| match x {
+            Binding {
+                modifier: None,
+                button: BindingButton::Key(key),
+            } => Some(*key),
+            _ => None,
+        })
+    }
+
+    pub fn apply(&self, controller: &mut FpsController) {
+        for (action, key) in [
+            (Action::Forward, &mut controller.key_forward),
+            (Action::Back, &mut controller.key_back),
+            (Action::Left, &mut controller.key_left),
+            (Action::Right, &mut controller.key_right),
+            (Action::Jump, &mut controller.key_jump),
+            (Action::Crouch, &mut controller.key_crouch),
+            (Action::Sprint, &mut controller.key_sprint),
+            (Action::Fly, &mut controller.key_fly),
+            (Action::FlyUp, &mut controller.key_up),
+            (Action::FlyDown, &mut controller.key_down),
+        ] {
+            *key = self.key(action).unwrap_or(UNBOUND_KEY);
+        }
+    }
+}
+
+/// State of the [`Action`]s through their [`Bindings`].
+#[derive(SystemParam)]
+pub struct ActionInput<'w, 's> {
+    bindings: Res<'w, Bindings>,
+    keys: Res<'w, ButtonInput<KeyCode>>,
+    mouse: Res<'w, ButtonInput<MouseButton>>,
+    gamepads: Query<'w, 's, &'static Gamepad>,
+}
+
+impl ActionInput<'_, '_> {
+    pub fn pressed(&self, action: Action) -> bool {
+        self.any(
+            action,
+            |keys, key| keys.pressed(key),
+            |mouse, button| mouse.pressed(button),
+            |gamepad, button| gamepad.pressed(button),
+        )
+    }
+
+    /// 1 when only `positive` is pressed, -1 when only `negative` is.
+    pub fn axis(&self, positive: Action, negative: Action) -> f32 {
+        match (self.pressed(positive), self.pressed(negative)) {
+            (true, false) => 1.,
+            (false, true) => -1.,
+            _ => 0.,
+        }
+    }
+
+    pub fn just_pressed(&self, action: Action) -> bool {
+        self.any(
+            action,
+            |keys, key| keys.just_pressed(key),
+            |mouse, button| mouse.just_pressed(button),
+            |gamepad, button| gamepad.just_pressed(button),
+        )
+    }
+
+    fn any(
+        &self,
+        action: Action,
+        key: impl Fn(&ButtonInput<KeyCode>, KeyCode) -> bool,
+        mouse: impl Fn(&ButtonInput<MouseButton>, MouseButton) -> bool,
+        gamepad: impl Fn(&Gamepad, GamepadButton) -> bool,
+    ) -> bool {
+        self.bindings.get(action).iter().any(|binding| {
+            let modifier = binding.modifier.is_none_or(|x| self.keys.pressed(x));
+            let button = match binding.button {
+                BindingButton::Key(x) => key(&self.keys, x),
+                BindingButton::Mouse(x) => mouse(&self.mouse, x),
+                BindingButton::Gamepad(x) => self.gamepads.iter().any(|g| gamepad(g, x)),
+            };
+
+            modifier && button
+        })
+    }
+}
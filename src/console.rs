@@ -2,7 +2,6 @@ use std::num::NonZeroUsize;
 
 use bevy::prelude::*;
 use bevy_console::*;
-use bevy_dev_tools::fps_overlay::FpsOverlayConfig;
 use bevy_fps_controller::controller::*;
 use clap::Parser;
 
@@ -18,7 +17,17 @@ impl Plugin for ConsolePlugin {
             .add_console_command::<DebugCommand, _>(debug)
             .add_console_command::<PauseCommand, _>(pause)
             .add_console_command::<NoClipCommand, _>(noclip)
-            .add_console_command::<FpsCommand, _>(fps);
+            .add_console_command::<FpsCommand, _>(fps)
+            .add_console_command::<TimescaleCommand, _>(timescale)
+            .add_console_command::<ReplayCommand, _>(replay)
+            .add_console_command::<GhostCommand, _>(ghost)
+            .add_console_command::<NameCommand, _>(name)
+            .add_console_command::<TimeFormatCommand, _>(time_format)
+            .add_console_command::<PbCommand, _>(pb)
+            .add_console_command::<PracticeCommand, _>(practice)
+            .add_console_command::<BindCommand, _>(bind)
+            .add_console_command::<ScrollJumpCommand, _>(scroll_jump)
+            .add_console_command::<CvarCommand, _>(cvar);
     }
 }
 
@@ -42,7 +51,12 @@ struct LevelCommand {
     level: usize,
 }
 
-fn level(mut log: ConsoleCommand<LevelCommand>, mut ew: MessageWriter<SpawnLevel>) {
+fn level(
+    mut log: ConsoleCommand<LevelCommand>,
+    mut ew: MessageWriter<SpawnLevel>,
+    levels: Res<Levels>,
+    mut integrity: ResMut<RunIntegrity>,
+) {
     if let Some(Ok(LevelCommand { level })) = log.take() {
         reply!(log, "Loading Level {level}");
 
@@ -51,14 +65,16 @@ fn level(mut log: ConsoleCommand<LevelCommand>, mut ew: MessageWriter<SpawnLevel
             return;
         };
 
-        if level.get() > LEVEL_COUNT {
+        if level.get() > levels.count() {
             reply!(
                 log,
-                "Level {level} does not exist! The MAX Level is {LEVEL_COUNT}."
+                "Level {level} does not exist! The MAX Level is {}.",
+                levels.count()
             );
             return;
         };
 
+        integrity.invalidate_run(Violation::LevelSkip);
         ew.write(SpawnLevel(level));
     }
 }
@@ -80,16 +96,37 @@ fn noclip(mut log: ConsoleCommand<NoClipCommand>, mut q_controller: Query<&mut F
     }
 }
 
+#[derive(Parser, ConsoleCommand)]
+#[command(name = "timescale")]
+struct TimescaleCommand {
+    #[arg(index = 1, default_value_t = 1.)]
+    scale: f32,
+}
+
+fn timescale(mut log: ConsoleCommand<TimescaleCommand>, mut time: ResMut<Time<Virtual>>) {
+    let Some(Ok(TimescaleCommand { scale })) = log.take() else {
+        return;
+    };
+
+    if scale <= 0. {
+        reply!(log, "Timescale must be greater than 0!");
+        return;
+    }
+
+    time.set_relative_speed(scale);
+    reply!(log, "Timescale is now {scale}.");
+}
+
 #[derive(Parser, ConsoleCommand)]
 #[command(name = "fps")]
 struct FpsCommand {}
 
-fn fps(mut log: ConsoleCommand<FpsCommand>, mut overlay: ResMut<FpsOverlayConfig>) {
+fn fps(mut log: ConsoleCommand<FpsCommand>, mut settings: ResMut<Settings>) {
     let Some(Ok(FpsCommand {})) = log.take() else {
         return;
     };
 
-    overlay.enabled = !overlay.enabled;
+    settings.fps_overlay = !settings.fps_overlay;
 }
 
 #[derive(Parser, ConsoleCommand)]
@@ -141,3 +178,344 @@ fn pause(
         }
     })
 }
+
+#[derive(Parser, ConsoleCommand)]
+#[command(name = "replay")]
+struct ReplayCommand {
+    /// Level to play the last replay of, the last full run if omitted.
+    #[arg(index = 1)]
+    level: Option<usize>,
+}
+
+fn replay(
+    mut log: ConsoleCommand<ReplayCommand>,
+    mut cmd: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    state: Res<State<AppState>>,
+    save_data: Res<SaveData>,
+) {
+    let Some(Ok(ReplayCommand { level })) = log.take() else {
+        return;
+    };
+
+    if *state.get() != AppState::InGame {
+        reply!(log, "Replays can only be played in game!");
+        return;
+    }
+
+    let key = match level.map(NonZeroUsize::new) {
+        Some(Some(level)) => Replay::level_key(level),
+        Some(None) => {
+            reply!(log, "Level must be greater than 0!");
+            return;
+        }
+        None => Replay::run_key(),
+    };
+
+    match Replay::load(&key) {
+        Ok(Some(replay)) => {
+            match replay.result() {
+                Some(time) => reply!(
+                    log,
+                    "Playing {key} ({})",
+                    save_data.time_format.format(time)
+                ),
+                None => reply!(log, "Playing {key}"),
+            }
+            spawn_replay_playback(&mut cmd, &mut meshes, &mut materials, replay);
+        }
+        Ok(None) => reply!(log, "No replay saved at {key}!"),
+        Err(e) => reply!(log, "Failed to load {key}: {e}"),
+    }
+}
+
+#[derive(Parser, ConsoleCommand)]
+#[command(name = "ghost")]
+struct GhostCommand {}
+
+fn ghost(mut log: ConsoleCommand<GhostCommand>, mut settings: ResMut<GhostSettings>) {
+    let Some(Ok(GhostCommand {})) = log.take() else {
+        return;
+    };
+
+    settings.enabled = !settings.enabled;
+
+    match settings.enabled {
+        true => reply!(
+            log,
+            "Ghost Enabled! It will race you from the next level start."
+        ),
+        false => reply!(log, "Ghost Disabled!"),
+    }
+}
+
+#[derive(Parser, ConsoleCommand)]
+#[command(name = "name")]
+struct NameCommand {
+    #[arg(index = 1)]
+    name: Option<String>,
+}
+
+fn name(mut log: ConsoleCommand<NameCommand>, mut save_data: ResMut<SaveData>) {
+    let Some(Ok(NameCommand { name })) = log.take() else {
+        return;
+    };
+
+    let Some(name) = name else {
+        reply!(log, "Your name is {}.", save_data.player_name);
+        return;
+    };
+
+    save_data.player_name = name;
+
+    if let Err(e) = save_data.save() {
+        reply!(log, "Failed to save name: {e}");
+        return;
+    }
+
+    reply!(log, "Your name is now {}.", save_data.player_name);
+}
+
+#[derive(Parser, ConsoleCommand)]
+#[command(name = "time_format")]
+struct TimeFormatCommand {
+    /// One of `millis`, `centis` or `ticks`.
+    #[arg(index = 1)]
+    format: Option<String>,
+}
+
+fn time_format(mut log: ConsoleCommand<TimeFormatCommand>, mut save_data: ResMut<SaveData>) {
+    let Some(Ok(TimeFormatCommand { format })) = log.take() else {
+        return;
+    };
+
+    let Some(format) = format else {
+        reply!(log, "Time format is {}.", save_data.time_format.name());
+        return;
+    };
+
+    let Some(format) = TimeFormat::from_name(&format) else {
+        reply!(
+            log,
+            "Unknown time format {format}! Use one of {}.",
+            TimeFormat::ALL.map(|x| x.name()).join(", ")
+        );
+        return;
+    };
+
+    save_data.time_format = format;
+
+    if let Err(e) = save_data.save() {
+        reply!(log, "Failed to save time format: {e}");
+        return;
+    }
+
+    reply!(log, "Time format is now {}.", format.name());
+}
+
+#[derive(Parser, ConsoleCommand)]
+#[command(name = "pb")]
+struct PbCommand {
+    /// Level to list the leaderboard of, full runs if omitted.
+    #[arg(index = 1)]
+    level: Option<usize>,
+}
+
+fn pb(mut log: ConsoleCommand<PbCommand>, save_data: Res<SaveData>) {
+    let Some(Ok(PbCommand { level })) = log.take() else {
+        return;
+    };
+
+    let leaderboard = match level {
+        Some(level) => save_data.levels.get(&level),
+        None => Some(&save_data.runs),
+    };
+
+    let Some(leaderboard) = leaderboard.filter(|x| !x.0.is_empty()) else {
+        reply!(log, "No times saved yet!");
+        return;
+    };
+
+    for (i, record) in leaderboard.0.iter().enumerate() {
+        reply!(
+            log,
+            "{}. {} {}",
+            i + 1,
+            save_data.time_format.format(record.time),
+            record.player_name
+        );
+    }
+}
+
+#[derive(Parser, ConsoleCommand)]
+#[command(name = "practice")]
+struct PracticeCommand {
+    #[arg(index = 1, value_enum)]
+    action: PracticeAction,
+}
+
+fn practice(
+    mut log: ConsoleCommand<PracticeCommand>,
+    mut ew: MessageWriter<PracticeAction>,
+    practice: Res<Practice>,
+) {
+    let Some(Ok(PracticeCommand { action })) = log.take() else {
+        return;
+    };
+
+    ew.write(action);
+
+    match action {
+        PracticeAction::Save => reply!(
+            log,
+            "Saved state {}. Teleporting to it won't count towards personal bests.",
+            practice.save_states.len() + 1
+        ),
+        PracticeAction::Clear => reply!(log, "Cleared save states!"),
+        _ => {}
+    }
+}
+
+#[derive(Parser, ConsoleCommand)]
+#[command(name = "bind")]
+struct BindCommand {
+    /// Action to bind, like `Jump` or `ResetLevel`.
+    #[arg(index = 1)]
+    action: String,
+    /// Like `KeyR`, `ShiftLeft+KeyR` or `MouseLeft`, shows the current bindings if omitted.
+    #[arg(index = 2)]
+    binding: Option<String>,
+}
+
+fn bind(mut log: ConsoleCommand<BindCommand>, mut bindings: ResMut<Bindings>) {
+    let Some(Ok(BindCommand { action, binding })) = log.take() else {
+        return;
+    };
+
+    let Some(action) = Action::from_name(&action) else {
+        reply!(log, "Unknown action {action}!");
+        return;
+    };
+
+    let Some(binding) = binding else {
+        let current = bindings.get(action).iter().map(|x| x.to_string());
+        reply!(
+            log,
+            "{action:?} is bound to {}.",
+            current.collect::<Vec<_>>().join(", ")
+        );
+        return;
+    };
+
+    let Some(binding) = Binding::parse(&binding) else {
+        reply!(log, "Unknown binding {binding}!");
+        return;
+    };
+
+    bindings.bind(action, binding);
+
+    if let Err(e) = bindings.save() {
+        reply!(log, "Failed to save bindings: {e}");
+        return;
+    }
+
+    let current = bindings.get(action).iter().map(|x| x.to_string());
+    reply!(
+        log,
+        "{action:?} is now bound to {}.",
+        current.collect::<Vec<_>>().join(", ")
+    );
+}
+
+#[derive(Parser, ConsoleCommand)]
+#[command(name = "scroll_jump")]
+struct ScrollJumpCommand {
+    #[arg(index = 1, value_enum)]
+    direction: Option<ScrollJumpDirection>,
+    /// Ticks jump is held for each line scrolled.
+    #[arg(index = 2)]
+    sensitivity: Option<f32>,
+}
+
+fn scroll_jump(mut log: ConsoleCommand<ScrollJumpCommand>, mut settings: ResMut<Settings>) {
+    let Some(Ok(ScrollJumpCommand {
+        direction,
+        sensitivity,
+    })) = log.take()
+    else {
+        return;
+    };
+
+    let scroll_jump = &mut settings.scroll_jump;
+
+    if let Some(direction) = direction {
+        scroll_jump.direction = direction;
+    }
+
+    if let Some(sensitivity) = sensitivity {
+        scroll_jump.sensitivity = sensitivity.max(0.);
+    }
+
+    reply!(
+        log,
+        "Scroll jump: {:?} with a sensitivity of {}.",
+        scroll_jump.direction,
+        scroll_jump.sensitivity
+    );
+}
+
+#[derive(Parser, ConsoleCommand)]
+#[command(name = "cvar")]
+struct CvarCommand {
+    /// Cvar to show or set, like `sv_airaccelerate`, lists all cvars if omitted.
+    #[arg(index = 1)]
+    name: Option<String>,
+    #[arg(index = 2)]
+    value: Option<f32>,
+}
+
+fn cvar(mut log: ConsoleCommand<CvarCommand>, mut cvars: CvarAccess) {
+    let Some(Ok(CvarCommand { name, value })) = log.take() else {
+        return;
+    };
+
+    let Some(name) = name else {
+        for cvar in Cvar::ALL {
+            reply!(
+                log,
+                "{} {} (default {}) - {}",
+                cvar.name(),
+                cvars.get(cvar),
+                cvar.default_value(),
+                cvar.description()
+            );
+        }
+        return;
+    };
+
+    let Some(cvar) = Cvar::from_name(&name) else {
+        reply!(log, "Unknown cvar {name}!");
+        return;
+    };
+
+    let Some(value) = value else {
+        reply!(log, "{name} is {}.", cvars.get(cvar));
+        return;
+    };
+
+    let range = cvar.range();
+
+    if !range.contains(&value) {
+        reply!(
+            log,
+            "{name} must be between {} and {}!",
+            range.start(),
+            range.end()
+        );
+        return;
+    }
+
+    cvars.set(cvar, value);
+    reply!(log, "{name} is now {value}.");
+}
@@ -1,18 +1,25 @@
-use std::{marker::PhantomData, num::NonZeroUsize};
+use std::{f32::consts::TAU, marker::PhantomData, num::NonZeroUsize, time::Duration};
 
 use avian3d::{PhysicsPlugins, prelude::*};
 use bevy::{
     asset::{AssetMetaCheck, LoadState},
     prelude::*,
 };
-use bevy_fps_controller::controller::LogicalPlayer;
+use bevy_fps_controller::controller::{FpsControllerInput, LogicalPlayer};
 use bevy_hanabi::EffectAsset;
 use bevy_skein::SkeinPlugin;
 
 pub use crate::state::*;
+use crate::{duration::Split, level::Levels, world::CurrentLevel};
+
+/// Rate of the fixed physics tick.
+pub const TICK_HZ: u16 = 128;
 
-pub const LEVEL_COUNT: usize = 3;
 pub const SPAWN_POINT: Vec3 = Vec3::new(0.0, 8., 0.0);
+pub const SPAWN_FACING: Facing = Facing {
+    yaw: TAU * 5.0 / 8.0,
+    pitch: -TAU / 12.0,
+};
 
 #[derive(Resource, Default)]
 pub struct AssetsLoading(pub Vec<UntypedHandle>);
@@ -26,38 +33,107 @@ impl AssetsLoading {
     }
 }
 
-#[derive(Resource)]
-pub struct Sounds {
-    pub dive_sound: Handle<AudioSource>,
-    pub ocean_sound: Handle<AudioSource>,
-    pub boost_sound: Handle<AudioSource>,
-    pub glass_sound: Handle<AudioSource>,
-    pub shatter_sound: Handle<AudioSource>,
-    pub land_sound: Handle<AudioSource>,
+#[derive(Message)]
+pub struct SpawnLevel(pub NonZeroUsize);
+
+#[derive(Message)]
+pub struct LevelFinished {
+    pub level: NonZeroUsize,
+    pub splits: Vec<Split>,
 }
 
 #[derive(Message)]
-pub struct SpawnLevel(pub NonZeroUsize);
+pub struct CheckpointReached(pub Split);
 
+/// The player restarted the current level from its spawn point.
 #[derive(Message)]
-pub struct Respawn<S: Component> {
+pub struct LevelReset;
+
+/// Yaw and pitch in radians, as used by [`FpsControllerInput`].
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct Facing {
+    pub yaw: f32,
+    pub pitch: f32,
+}
+
+impl Facing {
+    /// Faces along the horizontal part of `direction`, with no pitch.
+    pub fn from_direction(direction: Vec3) -> Self {
+        Self {
+            yaw: f32::atan2(-direction.x, -direction.z),
+            pitch: 0.,
+        }
+    }
+
+    /// Horizontal unit vector the yaw is facing.
+    pub fn forward(&self) -> Vec3 {
+        Quat::from_rotation_y(self.yaw) * Vec3::NEG_Z
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SpawnTransform {
     pub translation: Vec3,
+    /// Applied to the [`FpsControllerInput`] of the respawned unit, if it has one.
+    pub facing: Option<Facing>,
+    pub velocity: Vec3,
+}
+
+impl Default for SpawnTransform {
+    fn default() -> Self {
+        Self {
+            translation: SPAWN_POINT,
+            facing: Some(SPAWN_FACING),
+            velocity: Vec3::ZERO,
+        }
+    }
+}
+
+/// Why an entity is moved to a spawn point.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum RespawnCause {
+    /// Placed at the level start.
+    #[default]
+    Placement,
+    /// Fell past the out of bounds height.
+    Fell,
+    /// Touched a [`KillZone`].
+    KillZone,
+    /// Reset by the player.
+    Reset,
+}
+
+#[derive(Message)]
+pub struct Respawn<S: Component> {
+    pub spawn: SpawnTransform,
+    pub cause: RespawnCause,
     _marker: PhantomData<S>,
 }
 
 impl<S: Component> Respawn<S> {
-    pub fn new(translation: Vec3) -> Respawn<S> {
+    pub fn new(spawn: SpawnTransform, cause: RespawnCause) -> Respawn<S> {
         Self {
-            translation,
+            spawn,
+            cause,
             ..default()
         }
     }
+
+    pub fn placement(spawn: SpawnTransform) -> Respawn<S> {
+        Self { spawn, ..default() }
+    }
+
+    /// Whether the respawn follows a reset or failure, rather than placing `S` at the level start.
+    pub fn failed(&self) -> bool {
+        self.cause != RespawnCause::Placement
+    }
 }
 
 impl<S: Component> Default for Respawn<S> {
     fn default() -> Self {
         Self {
-            translation: default(),
+            spawn: default(),
+            cause: default(),
             _marker: default(),
         }
     }
@@ -72,6 +148,8 @@ pub enum CollisionLayer {
     Boost,
     Checkpoint,
     End,
+    KillZone,
+    Ghost,
 }
 
 #[derive(Component, Reflect, Debug)]
@@ -92,9 +170,70 @@ pub struct Prop;
 #[reflect(Component)]
 pub struct Ground;
 
+/// What a [`Ground`] surface is made of, picks the footstep, landing and slide sounds.
+#[derive(Component, Reflect, Debug, Default, Clone, Copy, PartialEq, Eq)]
+#[reflect(Component, Default)]
+pub enum SurfaceMaterial {
+    #[default]
+    Stone,
+    Metal,
+    Glass,
+    Wood,
+}
+
 #[derive(Component, Reflect, Debug)]
-#[reflect(Component)]
-pub struct CheckPoint;
+#[reflect(Component, Default)]
+pub struct CheckPoint {
+    /// Checkpoints have to be reached in ascending order, checkpoints sharing an index are alternatives.
+    pub index: u32,
+    /// Respawn position relative to the checkpoint.
+    pub offset: Vec3,
+    /// Respawn facing yaw in degrees, unless facing the entry direction.
+    pub yaw: f32,
+    /// Respawn facing pitch in degrees.
+    pub pitch: f32,
+    /// Face the horizontal direction the checkpoint was entered in.
+    pub face_entry_direction: bool,
+    /// Respawn with the horizontal speed the checkpoint was entered with.
+    pub preserve_speed: bool,
+}
+
+impl Default for CheckPoint {
+    fn default() -> Self {
+        Self {
+            index: 0,
+            offset: Vec3::Z * 4.,
+            yaw: 0.,
+            pitch: 0.,
+            face_entry_direction: true,
+            preserve_speed: false,
+        }
+    }
+}
+
+impl CheckPoint {
+    pub fn spawn_transform(&self, gtf: &GlobalTransform, entry_velocity: Vec3) -> SpawnTransform {
+        let entry_direction = entry_velocity.with_y(0.);
+
+        let yaw = match self.face_entry_direction && entry_direction != Vec3::ZERO {
+            true => Facing::from_direction(entry_direction).yaw,
+            false => self.yaw.to_radians(),
+        };
+        let facing = Facing {
+            yaw,
+            pitch: self.pitch.to_radians(),
+        };
+
+        SpawnTransform {
+            translation: gtf.translation() + self.offset,
+            facing: Some(facing),
+            velocity: match self.preserve_speed {
+                true => facing.forward() * entry_direction.length(),
+                false => Vec3::ZERO,
+            },
+        }
+    }
+}
 
 #[derive(Component, Reflect, Debug)]
 #[reflect(Component)]
@@ -104,23 +243,111 @@ pub struct End;
 #[reflect(Component)]
 pub struct SpeedBoost(pub f32);
 
+/// Volume that respawns players and props touching it.
+#[derive(Component, Reflect, Debug)]
+#[reflect(Component)]
+pub struct KillZone;
+
+/// Level start, placed in Blender through Skein. The position is taken from the entity's transform.
+#[derive(Component, Reflect, Debug, Default)]
+#[reflect(Component, Default)]
+pub struct SpawnPoint {
+    /// Facing yaw in degrees.
+    pub yaw: f32,
+    /// Facing pitch in degrees.
+    pub pitch: f32,
+}
+
+impl SpawnPoint {
+    pub fn spawn_transform(&self, gtf: &GlobalTransform) -> SpawnTransform {
+        SpawnTransform {
+            translation: gtf.translation(),
+            facing: Some(Facing {
+                yaw: self.yaw.to_radians(),
+                pitch: self.pitch.to_radians(),
+            }),
+            velocity: Vec3::ZERO,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct HistoryEntry {
+    pub checkpoint: Entity,
+    pub index: u32,
+    pub entry_velocity: Vec3,
+    /// Level time the checkpoint was reached at.
+    pub split: Duration,
+}
+
 #[derive(Resource, Debug, Default)]
-pub struct History(pub Vec<Entity>);
+pub struct History {
+    pub checkpoints: Vec<HistoryEntry>,
+    /// Start of the current level, used when no checkpoint was reached yet.
+    pub spawn: SpawnTransform,
+}
 
 impl History {
-    pub fn last(&self, q_gtf: Query<&GlobalTransform, With<CheckPoint>>) -> Vec3 {
-        if let Some(check_point) = self.0.last()
-            && let Ok(gtf) = q_gtf.get(*check_point)
+    pub fn last(&self, q_checkpoint: Query<(&GlobalTransform, &CheckPoint)>) -> SpawnTransform {
+        if let Some(entry) = self.checkpoints.last()
+            && let Ok((gtf, check_point)) = q_checkpoint.get(entry.checkpoint)
         {
-            let t = gtf.translation();
-            return t.with_z(t.z + 4.);
+            return check_point.spawn_transform(gtf, entry.entry_velocity);
         };
 
-        SPAWN_POINT
+        self.spawn
     }
 
     pub fn empty(&self) -> bool {
-        self.0.is_empty()
+        self.checkpoints.is_empty()
+    }
+
+    /// Lowest index of `indices` that still has to be reached.
+    pub fn next_index(&self, indices: impl Iterator<Item = u32>) -> Option<u32> {
+        let last = self.checkpoints.last().map(|x| x.index);
+
+        indices
+            .filter(|index| last.is_none_or(|last| *index > last))
+            .min()
+    }
+
+    /// Whether reaching `checkpoint` continues the route without skipping or repeating a checkpoint.
+    pub fn accepts(
+        &self,
+        checkpoint: Entity,
+        index: u32,
+        indices: impl Iterator<Item = u32>,
+    ) -> bool {
+        if self.checkpoints.iter().any(|x| x.checkpoint == checkpoint) {
+            return false;
+        }
+
+        let is_alternative = self.checkpoints.last().is_some_and(|x| x.index == index);
+
+        is_alternative || self.next_index(indices) == Some(index)
+    }
+
+    /// Splits of the first checkpoint reached for each index.
+    pub fn splits(&self) -> Vec<Split> {
+        let mut splits: Vec<Split> = Vec::new();
+
+        for entry in &self.checkpoints {
+            if splits.last().is_some_and(|x| x.index == entry.index) {
+                continue;
+            }
+
+            splits.push(Split {
+                index: entry.index,
+                time: entry.split,
+            });
+        }
+
+        splits
+    }
+
+    pub fn reset(&mut self) {
+        self.checkpoints.clear();
+        self.spawn = default();
     }
 }
 
@@ -135,8 +362,8 @@ pub struct ParticleEffects {
     pub boost_fx: Handle<EffectAsset>,
     pub boost_idle_fx: Handle<EffectAsset>,
     pub player_boost_fx: Handle<EffectAsset>,
-    pub new_level_fx: [Handle<EffectAsset>; LEVEL_COUNT],
-    pub checkpoint_fx: [Handle<EffectAsset>; LEVEL_COUNT],
+    pub new_level_fx: Vec<Handle<EffectAsset>>,
+    pub checkpoint_fx: Vec<Handle<EffectAsset>>,
 }
 
 impl ParticleEffects {
@@ -154,8 +381,11 @@ pub struct CorePlugin;
 impl Plugin for CorePlugin {
     fn build(&self, app: &mut App) {
         app.add_message::<SpawnLevel>()
+            .add_message::<LevelFinished>()
+            .add_message::<CheckpointReached>()
+            .add_message::<LevelReset>()
             .insert_resource(AssetsLoading::default())
-            .insert_resource(Time::<Fixed>::from_hz(128.0))
+            .insert_resource(Time::<Fixed>::from_hz(TICK_HZ as f64))
             .insert_resource(History::default())
             .register_type::<Prop>()
             .register_type::<Character>()
@@ -166,6 +396,9 @@ impl Plugin for CorePlugin {
             .register_type::<End>()
             .register_type::<SpeedBoost>()
             .register_type::<Ground>()
+            .register_type::<SurfaceMaterial>()
+            .register_type::<SpawnPoint>()
+            .register_type::<KillZone>()
             .add_plugins((
                 DefaultPlugins
                     .set(AssetPlugin {
@@ -235,13 +468,27 @@ pub fn cleanup<S: Component>(mut cmd: Commands, q: Query<Entity, With<S>>) {
 }
 
 pub fn respawn<S: Component>(
-    mut q: Query<(&mut Transform, &mut LinearVelocity), With<S>>,
+    mut q: Query<
+        (
+            &mut Transform,
+            &mut LinearVelocity,
+            Option<&mut FpsControllerInput>,
+        ),
+        With<S>,
+    >,
     mut er: MessageReader<Respawn<S>>,
 ) {
     for e in er.read() {
-        for (mut transform, mut velocity) in &mut q {
-            velocity.0 = Vec3::ZERO;
-            transform.translation = e.translation;
+        for (mut transform, mut velocity, input) in &mut q {
+            velocity.0 = e.spawn.velocity;
+            transform.translation = e.spawn.translation;
+
+            if let Some(mut input) = input
+                && let Some(facing) = e.spawn.facing
+            {
+                input.yaw = facing.yaw;
+                input.pitch = facing.pitch;
+            }
         }
     }
 }
@@ -249,20 +496,28 @@ pub fn respawn<S: Component>(
 pub fn out_of_bounds<S: Component>(
     q: Query<&Transform, With<S>>,
     history: Res<History>,
-    q_gtf: Query<&GlobalTransform, With<CheckPoint>>,
+    q_checkpoint: Query<(&GlobalTransform, &CheckPoint)>,
     mut er: MessageWriter<Respawn<S>>,
+    q_kill_zone: Query<(), With<KillZone>>,
+    levels: Res<Levels>,
+    current_level: Res<CurrentLevel>,
 ) {
-    let spawn_point = history.last(q_gtf);
+    let level = levels.get(current_level.get());
+    let Some(height) = level.out_of_bounds_limit(!q_kill_zone.is_empty()) else {
+        return;
+    };
+    let spawn_point = history.last(q_checkpoint);
 
     for transform in &q {
-        if !is_out_of_bounds(transform.translation, spawn_point) {
+        if !is_out_of_bounds(transform.translation, spawn_point.translation, height) {
             continue;
         }
 
-        er.write(Respawn::<S>::new(spawn_point));
+        er.write(Respawn::<S>::new(spawn_point, RespawnCause::Fell));
     }
 }
 
-pub fn is_out_of_bounds(translation: Vec3, spawn_point: Vec3) -> bool {
-    (spawn_point.y - translation.y).abs() >= 95.
+/// Fallback to [`KillZone`]s, relative to the last spawn point.
+pub fn is_out_of_bounds(translation: Vec3, spawn_point: Vec3, height: f32) -> bool {
+    (spawn_point.y - translation.y).abs() >= height
 }
@@ -0,0 +1,198 @@
+use std::{collections::HashMap, ops::RangeInclusive};
+
+use bevy::{ecs::system::SystemParam, prelude::*};
+use bevy_fps_controller::controller::*;
+
+use crate::prelude::*;
+
+pub struct CvarPlugin;
+
+impl Plugin for CvarPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Cvars>()
+            .add_systems(Update, apply_cvars.run_if(resource_changed::<Cvars>));
+    }
+}
+
+/// Tuning variable set through the console. `sv_` cvars tune the movement and only last for the
+/// session, `cl_` and `m_` cvars are backed by the [`Settings`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Cvar {
+    AirAccelerate,
+    Accelerate,
+    Friction,
+    Gravity,
+    MaxSpeed,
+    WalkSpeed,
+    AirSpeedCap,
+    MaxAirSpeed,
+    JumpSpeed,
+    NoclipSpeed,
+    Fov,
+    Sensitivity,
+}
+
+impl Cvar {
+    pub const ALL: [Cvar; 12] = [
+        Cvar::AirAccelerate,
+        Cvar::Accelerate,
+        Cvar::Friction,
+        Cvar::Gravity,
+        Cvar::MaxSpeed,
+        Cvar::WalkSpeed,
+        Cvar::AirSpeedCap,
+        Cvar::MaxAirSpeed,
+        Cvar::JumpSpeed,
+        Cvar::NoclipSpeed,
+        Cvar::Fov,
+        Cvar::Sensitivity,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Cvar::AirAccelerate => "sv_airaccelerate",
+            Cvar::Accelerate => "sv_accelerate",
+            Cvar::Friction => "sv_friction",
+            Cvar::Gravity => "sv_gravity",
+            Cvar::MaxSpeed => "sv_maxspeed",
+            Cvar::WalkSpeed => "sv_walkspeed",
+            Cvar::AirSpeedCap => "sv_airspeedcap",
+            Cvar::MaxAirSpeed => "sv_maxairspeed",
+            Cvar::JumpSpeed => "sv_jumpspeed",
+            Cvar::NoclipSpeed => "sv_noclipspeed",
+            Cvar::Fov => "cl_fov",
+            Cvar::Sensitivity => "m_sensitivity",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|x| x.name() == name)
+    }
+
+    pub fn description(&self) -> &'static str {
+        match self {
+            Cvar::AirAccelerate => "Acceleration in the air, what makes strafing gain speed",
+            Cvar::Accelerate => "Acceleration on the ground",
+            Cvar::Friction => "Ground friction",
+            Cvar::Gravity => "Downward acceleration",
+            Cvar::MaxSpeed => "Sprinting speed on the ground",
+            Cvar::WalkSpeed => "Walking speed on the ground",
+            Cvar::AirSpeedCap => "Wish speed cap while strafing in the air",
+            Cvar::MaxAirSpeed => "Speed limit in the air",
+            Cvar::JumpSpeed => "Upward speed of a jump",
+            Cvar::NoclipSpeed => "Flying speed in noclip, doubled while sprinting",
+            Cvar::Fov => "Vertical field of view in degrees",
+            Cvar::Sensitivity => "Mouse sensitivity",
+        }
+    }
+
+    /// The controller's own defaults, except for what the game tunes for bunny hopping.
+    pub fn default_value(&self) -> f32 {
+        let controller = FpsController::default();
+        let settings = Settings::default();
+
+        match self {
+            Cvar::AirAccelerate => 20.,
+            Cvar::Accelerate => controller.acceleration,
+            Cvar::Friction => 10.,
+            Cvar::Gravity => controller.gravity,
+            Cvar::MaxSpeed => controller.run_speed,
+            Cvar::WalkSpeed => controller.walk_speed,
+            Cvar::AirSpeedCap => 10.,
+            Cvar::MaxAirSpeed => 1000.,
+            Cvar::JumpSpeed => controller.jump_speed,
+            Cvar::NoclipSpeed => 50.,
+            Cvar::Fov => settings.fov,
+            Cvar::Sensitivity => settings.mouse_sensitivity,
+        }
+    }
+
+    /// Same limits as the settings menu for the ones it shows.
+    pub fn range(&self) -> RangeInclusive<f32> {
+        match self {
+            Cvar::Fov => 50.0..=120.,
+            Cvar::Sensitivity => 0.05..=5.,
+            _ => 0.0..=f32::MAX,
+        }
+    }
+
+    fn apply(&self, controller: &mut FpsController, value: f32) {
+        match self {
+            Cvar::AirAccelerate => controller.air_acceleration = value,
+            Cvar::Accelerate => controller.acceleration = value,
+            Cvar::Friction => controller.friction = value,
+            Cvar::Gravity => controller.gravity = value,
+            Cvar::MaxSpeed => controller.run_speed = value,
+            Cvar::WalkSpeed => controller.walk_speed = value,
+            Cvar::AirSpeedCap => controller.air_speed_cap = value,
+            Cvar::MaxAirSpeed => controller.max_air_speed = value,
+            Cvar::JumpSpeed => controller.jump_speed = value,
+            Cvar::NoclipSpeed => {
+                controller.fly_speed = value;
+                controller.fast_fly_speed = value * 2.;
+            }
+            // Applied by the settings.
+            Cvar::Fov | Cvar::Sensitivity => {}
+        }
+    }
+}
+
+/// Values of the `sv_` cvars that were changed from their defaults.
+#[derive(Resource, Debug, Default)]
+pub struct Cvars(HashMap<Cvar, f32>);
+
+impl Cvars {
+    fn get(&self, cvar: Cvar) -> f32 {
+        self.0
+            .get(&cvar)
+            .copied()
+            .unwrap_or_else(|| cvar.default_value())
+    }
+
+    /// Tunes `controller` with the `sv_` cvars.
+    pub fn apply(&self, controller: &mut FpsController) {
+        for cvar in Cvar::ALL {
+            cvar.apply(controller, self.get(cvar));
+        }
+    }
+
+    /// Movement that isn't tuned the default way makes times incomparable.
+    pub fn is_default(&self) -> bool {
+        self.0
+            .iter()
+            .all(|(cvar, value)| *value == cvar.default_value())
+    }
+}
+
+/// Reads and writes cvars wherever they are stored.
+#[derive(SystemParam)]
+pub struct CvarAccess<'w> {
+    cvars: ResMut<'w, Cvars>,
+    settings: ResMut<'w, Settings>,
+}
+
+impl CvarAccess<'_> {
+    pub fn get(&self, cvar: Cvar) -> f32 {
+        match cvar {
+            Cvar::Fov => self.settings.fov,
+            Cvar::Sensitivity => self.settings.mouse_sensitivity,
+            cvar => self.cvars.get(cvar),
+        }
+    }
+
+    pub fn set(&mut self, cvar: Cvar, value: f32) {
+        match cvar {
+            Cvar::Fov => self.settings.fov = value,
+            Cvar::Sensitivity => self.settings.mouse_sensitivity = value,
+            cvar => {
+                self.cvars.0.insert(cvar, value);
+            }
+        }
+    }
+}
+
+fn apply_cvars(cvars: Res<Cvars>, mut q_controller: Query<&mut FpsController>) {
+    for mut controller in &mut q_controller {
+        cvars.apply(&mut controller);
+    }
+}
@@ -1,21 +1,92 @@
-use std::time::Duration;
+use std::{num::NonZeroUsize, time::Duration};
 
 use bevy::{prelude::*, time::Stopwatch};
 use bevy_fps_controller::controller::LogicalPlayer;
+use serde::{Deserialize, Serialize};
 
-use crate::core::*;
+use crate::{core::*, level::Levels, world::CurrentLevel};
 
+/// Time counted in fixed ticks, so it's the same for the same inputs on any hardware.
+#[derive(Reflect, Debug, Clone, Copy, Default)]
+pub struct TickStopwatch {
+    ticks: u64,
+    paused: bool,
+}
+
+impl TickStopwatch {
+    pub fn tick(&mut self) {
+        if !self.paused {
+            self.ticks += 1;
+        }
+    }
+
+    pub fn ticks(&self) -> u64 {
+        self.ticks
+    }
+
+    pub fn elapsed(&self) -> Duration {
+        Duration::from_nanos(self.ticks * 1_000_000_000 / TICK_HZ as u64)
+    }
+
+    pub fn reset(&mut self) {
+        self.ticks = 0;
+    }
+
+    pub fn pause(&mut self) {
+        self.paused = true;
+    }
+
+    pub fn unpause(&mut self) {
+        self.paused = false;
+    }
+}
+
+/// Level time, ending at the tick the `End` trigger is touched in.
 #[derive(Resource, Reflect, Debug, Default)]
-pub struct LevelDuration(pub Stopwatch);
+pub struct LevelDuration(pub TickStopwatch);
+
+/// Level time at which the checkpoint with `index` was first reached.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+pub struct Split {
+    pub index: u32,
+    pub time: Duration,
+}
 
 #[derive(Resource, Reflect, Debug, Default)]
 pub struct RunDuration {
-    pub results: [Duration; LEVEL_COUNT],
+    pub results: Vec<Duration>,
 }
 
 impl RunDuration {
     fn reset(&mut self) {
-        (0..LEVEL_COUNT).for_each(|x| self.results[x] = Duration::default());
+        self.results
+            .iter_mut()
+            .for_each(|x| *x = Duration::default());
+    }
+}
+
+/// Wall clock time of the current run from its start until `GameOver`, including resets, failed
+/// attempts and pauses.
+#[derive(Resource, Reflect, Debug, Default)]
+pub struct RealTimeDuration(pub Stopwatch);
+
+/// Attempts at each level during the current run.
+#[derive(Resource, Reflect, Debug, Default)]
+pub struct Attempts(pub Vec<u32>);
+
+impl Attempts {
+    pub fn get(&self, level: NonZeroUsize) -> u32 {
+        self.0.get(level.get() - 1).copied().unwrap_or_default()
+    }
+
+    fn increment(&mut self, level: NonZeroUsize) {
+        if let Some(attempts) = self.0.get_mut(level.get() - 1) {
+            *attempts += 1;
+        }
+    }
+
+    fn reset(&mut self) {
+        self.0.iter_mut().for_each(|x| *x = 0);
     }
 }
 
@@ -25,31 +96,74 @@ impl Plugin for DurationPlugin {
     fn build(&self, app: &mut App) {
         app.insert_resource(LevelDuration::default())
             .insert_resource(RunDuration::default())
-            .add_systems(Update, reset_timer)
+            .insert_resource(RealTimeDuration::default())
+            .insert_resource(Attempts::default())
+            .add_systems(Update, setup.run_if(resource_added::<Levels>))
+            // Physics and with it the level triggers run after `FixedUpdate`, so a tick is
+            // counted before the collisions in it.
+            .add_systems(
+                FixedUpdate,
+                (reset_timer, tick_level_duration.in_set(GameplaySet)).chain(),
+            )
+            .add_systems(Update, tick_real_time.run_if(in_state(AppState::InGame)))
             .add_systems(OnEnter(AppState::InGame), reset_run_duration);
     }
 }
 
-pub fn reset_run_duration(mut run_duration: ResMut<RunDuration>, mut timer: ResMut<LevelDuration>) {
+fn setup(
+    mut run_duration: ResMut<RunDuration>,
+    mut attempts: ResMut<Attempts>,
+    levels: Res<Levels>,
+) {
+    run_duration.results = vec![Duration::default(); levels.count()];
+    attempts.0 = vec![0; levels.count()];
+}
+
+pub fn reset_run_duration(
+    mut run_duration: ResMut<RunDuration>,
+    mut timer: ResMut<LevelDuration>,
+    mut real_time: ResMut<RealTimeDuration>,
+    mut attempts: ResMut<Attempts>,
+) {
     run_duration.reset();
     timer.0.reset();
+    real_time.0.reset();
+    real_time.0.unpause();
+    attempts.reset();
+    // The run starts at the first level without a `SpawnLevel`.
+    attempts.increment(NonZeroUsize::MIN);
 }
 
 fn reset_timer(
     mut er_respawn: MessageReader<Respawn<LogicalPlayer>>,
     mut timer: ResMut<LevelDuration>,
+    mut attempts: ResMut<Attempts>,
     history: Res<History>,
+    current_level: Res<CurrentLevel>,
     mut er_level: MessageReader<SpawnLevel>,
 ) {
-    for _ in er_level.read() {
+    for SpawnLevel(level) in er_level.read() {
         timer.0.reset();
+        attempts.increment(*level);
     }
 
     if !history.empty() {
         return;
     }
 
-    for _ in er_respawn.read() {
+    for e in er_respawn.read() {
         timer.0.reset();
+
+        if e.failed() {
+            attempts.increment(current_level.get());
+        }
     }
 }
+
+fn tick_level_duration(mut level_duration: ResMut<LevelDuration>) {
+    level_duration.0.tick();
+}
+
+fn tick_real_time(mut real_time: ResMut<RealTimeDuration>, time: Res<Time<Real>>) {
+    real_time.0.tick(time.delta());
+}
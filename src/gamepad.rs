@@ -0,0 +1,80 @@
+use std::f32::consts::FRAC_PI_2;
+
+use bevy::prelude::*;
+use bevy_fps_controller::controller::*;
+use serde::{Deserialize, Serialize};
+
+use crate::prelude::*;
+
+/// Keeps the view from flipping over when looking straight up or down.
+const PITCH_LIMIT: f32 = FRAC_PI_2 - 0.01;
+
+pub struct GamepadPlugin;
+
+impl Plugin for GamepadPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            PreUpdate,
+            gamepad_input
+                .after(binding_input)
+                .before(fps_controller_move),
+        );
+    }
+}
+
+/// Response of the gamepad sticks, `look_speed` is in radians per second at full deflection.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy)]
+pub struct StickSettings {
+    pub deadzone: f32,
+    pub exponent: f32,
+    pub look_speed: f32,
+}
+
+impl Default for StickSettings {
+    fn default() -> Self {
+        Self {
+            deadzone: 0.15,
+            exponent: 2.,
+            look_speed: 4.,
+        }
+    }
+}
+
+impl StickSettings {
+    /// Radial deadzone, rescaled so the output still starts at zero, followed by the response curve.
+    pub fn apply(&self, stick: Vec2) -> Vec2 {
+        let length = stick.length();
+
+        if length <= self.deadzone {
+            return Vec2::ZERO;
+        }
+
+        let scaled = ((length - self.deadzone) / (1. - self.deadzone)).min(1.);
+        stick / length * scaled.powf(self.exponent)
+    }
+}
+
+fn gamepad_input(
+    settings: Res<Settings>,
+    q_gamepad: Query<&Gamepad>,
+    mut q_player: Query<(&mut FpsControllerInput, &FpsController), With<LogicalPlayer>>,
+    time: Res<Time>,
+) {
+    let sticks = settings.sticks;
+
+    for (mut input, controller) in &mut q_player {
+        if !controller.enable_input {
+            continue;
+        }
+
+        for gamepad in &q_gamepad {
+            let movement = sticks.apply(gamepad.left_stick());
+            input.movement.x = (input.movement.x + movement.x).clamp(-1., 1.);
+            input.movement.z = (input.movement.z + movement.y).clamp(-1., 1.);
+
+            let look = sticks.apply(gamepad.right_stick()) * sticks.look_speed * time.delta_secs();
+            input.yaw -= look.x;
+            input.pitch = (input.pitch + look.y).clamp(-PITCH_LIMIT, PITCH_LIMIT);
+        }
+    }
+}
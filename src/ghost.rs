@@ -0,0 +1,93 @@
+use avian3d::prelude::*;
+use bevy::{
+    light::{NotShadowCaster, NotShadowReceiver},
+    prelude::*,
+};
+
+use crate::prelude::*;
+
+pub struct GhostPlugin;
+
+impl Plugin for GhostPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(GhostSettings::default())
+            .add_systems(OnEnter(AppState::InGame), spawn_ghost)
+            .add_systems(OnExit(AppState::InGame), cleanup::<Ghost>)
+            .add_systems(
+                Update,
+                (spawn_ghost.run_if(on_message::<SpawnLevel>), update_ghost)
+                    .chain()
+                    .in_set(GameplaySet),
+            );
+    }
+}
+
+#[derive(Resource, Debug, Default)]
+pub struct GhostSettings {
+    pub enabled: bool,
+}
+
+/// Translucent playback of the personal best replay of the current level, synced with [`LevelDuration`].
+#[derive(Component)]
+pub struct Ghost {
+    replay: Replay,
+}
+
+fn spawn_ghost(
+    mut cmd: Commands,
+    settings: Res<GhostSettings>,
+    current_level: Res<CurrentLevel>,
+    q_ghost: Query<Entity, With<Ghost>>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    for ghost in &q_ghost {
+        cmd.entity(ghost).despawn();
+    }
+
+    if !settings.enabled {
+        return;
+    }
+
+    let key = Replay::pb_key(current_level.get());
+    let replay = match Replay::load(&key) {
+        Ok(Some(replay)) => replay,
+        Ok(None) => return,
+        Err(e) => {
+            warn!("Failed to load ghost {key}: {e}");
+            return;
+        }
+    };
+
+    cmd.spawn((
+        Ghost { replay },
+        Mesh3d(meshes.add(Cylinder::new(1.0, 3.0))),
+        MeshMaterial3d(materials.add(StandardMaterial {
+            base_color: Resurrect64::LIGHT_BLUE.with_alpha(0.3),
+            alpha_mode: AlphaMode::Blend,
+            unlit: true,
+            ..default()
+        })),
+        Collider::cylinder(1.0, 3.0),
+        Sensor,
+        CollisionLayers::new(CollisionLayer::Ghost, LayerMask::NONE),
+        (NotShadowCaster, NotShadowReceiver),
+        Transform::default(),
+        Visibility::Hidden,
+    ));
+}
+
+fn update_ghost(
+    mut q_ghost: Query<(&Ghost, &mut Transform, &mut Visibility)>,
+    level_duration: Res<LevelDuration>,
+) {
+    for (ghost, mut transform, mut visibility) in &mut q_ghost {
+        let Some(frame) = ghost.replay.frame_at(level_duration.0.ticks()) else {
+            continue;
+        };
+
+        transform.translation = frame.translation;
+        transform.rotation = frame.facing();
+        *visibility = Visibility::Visible;
+    }
+}
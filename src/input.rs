@@ -1,5 +1,5 @@
 use bevy::{
-    input::mouse::MouseWheel,
+    input::mouse::{MouseScrollUnit, MouseWheel},
     prelude::*,
     window::{CursorGrabMode, CursorOptions},
 };
@@ -7,22 +7,67 @@ use bevy::{
 use bevy_fps_controller::controller::*;
 
 use avian_pickup::prelude::*;
+use clap::ValueEnum;
+use serde::{Deserialize, Serialize};
 
-use crate::core::*;
+use crate::{bindings::*, core::*, settings::Settings};
+
+/// Roughly one wheel notch, for devices that scroll in pixels.
+const PIXELS_PER_LINE: f32 = 100.;
+const MAX_SCROLL_JUMP_TICKS: u32 = 16;
 
 pub struct InputPlugin;
 
 #[derive(Component)]
 pub struct AutoJump;
 
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum ScrollJumpDirection {
+    Off,
+    Up,
+    Down,
+    Both,
+}
+
+/// Mouse wheel jumping, `sensitivity` is the number of ticks jump is held for each line scrolled.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy)]
+pub struct ScrollJump {
+    pub direction: ScrollJumpDirection,
+    pub sensitivity: f32,
+}
+
+impl Default for ScrollJump {
+    fn default() -> Self {
+        Self {
+            direction: ScrollJumpDirection::Down,
+            sensitivity: 1.,
+        }
+    }
+}
+
+/// Scrolled jump ticks that weren't simulated yet, so a scroll between fixed ticks isn't lost.
+#[derive(Resource, Debug, Default)]
+struct ScrollJumpBuffer {
+    ticks: u32,
+    remainder: f32,
+}
+
 impl Plugin for InputPlugin {
     fn build(&self, app: &mut App) {
         app.add_plugins((AvianPickupPlugin::default(), FpsControllerPlugin))
+            .insert_resource(Bindings::load())
+            .insert_resource(ScrollJumpBuffer::default())
+            .add_systems(
+                Update,
+                apply_bindings.run_if(
+                    resource_changed::<Bindings>.or(any_match_filter::<Added<FpsController>>),
+                ),
+            )
             .add_systems(
                 Update,
                 (
                     manage_cursor,
-                    scroll_events,
+                    buffer_scroll_jump,
                     handle_auto_jump,
                     handle_reset.before(respawn::<LogicalPlayer>),
                 )
@@ -37,10 +82,12 @@ impl Plugin for InputPlugin {
             )
             .add_systems(
                 PreUpdate,
-                auto_jump
+                (binding_input, auto_jump, scroll_jump)
                     .after(fps_controller_input)
                     .before(fps_controller_move),
             )
+            .add_systems(FixedPostUpdate, consume_scroll_jump)
+            .add_systems(OnExit(AppState::InGame), clear_scroll_jump)
             .add_systems(
                 RunFixedMainLoop,
                 handle_pickup
@@ -50,18 +97,45 @@ impl Plugin for InputPlugin {
     }
 }
 
+fn apply_bindings(bindings: Res<Bindings>, mut q_controller: Query<&mut FpsController>) {
+    for mut controller in &mut q_controller {
+        bindings.apply(&mut controller);
+    }
+}
+
+/// Drives the controller through all [`Bindings`], as its own keys only cover plain keys.
+pub fn binding_input(
+    actions: ActionInput,
+    mut q_player: Query<(&mut FpsControllerInput, &FpsController), With<LogicalPlayer>>,
+) {
+    for (mut input, controller) in &mut q_player {
+        if !controller.enable_input {
+            continue;
+        }
+
+        input.movement = Vec3::new(
+            actions.axis(Action::Right, Action::Left),
+            actions.axis(Action::FlyUp, Action::FlyDown),
+            actions.axis(Action::Forward, Action::Back),
+        );
+        input.jump |= actions.pressed(Action::Jump);
+        input.crouch |= actions.pressed(Action::Crouch);
+        input.sprint |= actions.pressed(Action::Sprint);
+        input.fly |= actions.just_pressed(Action::Fly);
+    }
+}
+
 fn manage_cursor(
-    btn: Res<ButtonInput<MouseButton>>,
-    key: Res<ButtonInput<KeyCode>>,
+    actions: ActionInput,
     cursor_query: Query<&mut CursorOptions>,
     controller_query: Query<&mut FpsController>,
     mut ns: ResMut<NextState<PausedState>>,
 ) {
-    if btn.just_pressed(MouseButton::Left) {
+    if actions.just_pressed(Action::CaptureCursor) {
         disable_cursor(cursor_query, controller_query);
     }
 
-    if key.just_pressed(KeyCode::Escape) {
+    if actions.just_pressed(Action::Pause) {
         ns.set(PausedState::Paused);
     }
 }
@@ -92,47 +166,75 @@ fn enable_cursor(
     }
 }
 
-fn scroll_events(mut er: MessageReader<MouseWheel>) {
-    use bevy::input::mouse::MouseScrollUnit;
+fn buffer_scroll_jump(
+    mut er: MessageReader<MouseWheel>,
+    settings: Res<Settings>,
+    mut buffer: ResMut<ScrollJumpBuffer>,
+) {
+    let scroll_jump = settings.scroll_jump;
+
     for ev in er.read() {
-        match ev.unit {
-            MouseScrollUnit::Line => {
-                println!(
-                    "Scroll (line units): vertical: {}, horizontal: {}",
-                    ev.y, ev.x
-                );
-            }
-            MouseScrollUnit::Pixel => {
-                println!(
-                    "Scroll (pixel units): vertical: {}, horizontal: {}",
-                    ev.y, ev.x
-                );
-            }
-        }
+        let lines = match ev.unit {
+            MouseScrollUnit::Line => ev.y,
+            MouseScrollUnit::Pixel => ev.y / PIXELS_PER_LINE,
+        };
+
+        let lines = match scroll_jump.direction {
+            ScrollJumpDirection::Off => 0.,
+            ScrollJumpDirection::Up => lines.max(0.),
+            ScrollJumpDirection::Down => -lines.min(0.),
+            ScrollJumpDirection::Both => lines.abs(),
+        };
+
+        buffer.remainder += lines * scroll_jump.sensitivity;
+        let ticks = buffer.remainder.floor();
+        buffer.remainder -= ticks;
+        buffer.ticks = (buffer.ticks + ticks as u32).min(MAX_SCROLL_JUMP_TICKS);
     }
 }
 
+fn scroll_jump(
+    buffer: Res<ScrollJumpBuffer>,
+    mut q_input: Query<&mut FpsControllerInput, With<LogicalPlayer>>,
+) {
+    if buffer.ticks == 0 {
+        return;
+    }
+
+    for mut input in &mut q_input {
+        input.jump = true;
+    }
+}
+
+fn consume_scroll_jump(mut buffer: ResMut<ScrollJumpBuffer>) {
+    buffer.ticks = buffer.ticks.saturating_sub(1);
+}
+
+fn clear_scroll_jump(mut buffer: ResMut<ScrollJumpBuffer>) {
+    *buffer = default();
+}
+
 fn handle_pickup(
     mut ew: MessageWriter<AvianPickupInput>,
-    keys: Res<ButtonInput<MouseButton>>,
+    actions: ActionInput,
     actors: Query<Entity, With<AvianPickupActor>>,
 ) {
     for actor in &actors {
-        if keys.just_pressed(MouseButton::Left) {
+        if actions.just_pressed(Action::Throw) {
             ew.write(AvianPickupInput {
                 action: AvianPickupAction::Throw,
                 actor,
             });
         }
 
-        if keys.just_pressed(MouseButton::Right) {
+        if actions.just_pressed(Action::Drop) {
             ew.write(AvianPickupInput {
                 action: AvianPickupAction::Drop,
                 actor,
             });
         }
 
-        if keys.pressed(MouseButton::Right) {
+        if actions.pressed(Action::Pickup) {
             ew.write(AvianPickupInput {
                 action: AvianPickupAction::Pull,
                 actor,
@@ -150,10 +252,10 @@ fn auto_jump(mut q_input: Query<&mut FpsControllerInput, With<AutoJump>>) {
 fn handle_auto_jump(
     mut cmd: Commands,
     q_player: Query<(Entity, Option<&AutoJump>), With<FpsControllerInput>>,
-    keys: Res<ButtonInput<KeyCode>>,
+    actions: ActionInput,
 ) {
     {
-        if !keys.just_pressed(KeyCode::Space) || !keys.pressed(KeyCode::ShiftLeft) {
+        if !actions.just_pressed(Action::ToggleAutoJump) {
             return;
         }
 
@@ -169,20 +271,27 @@ fn handle_auto_jump(
 }
 
 fn handle_reset(
-    keys: Res<ButtonInput<KeyCode>>,
+    actions: ActionInput,
     mut ew: MessageWriter<Respawn<LogicalPlayer>>,
+    mut ew_reset: MessageWriter<LevelReset>,
     mut history: ResMut<History>,
-    q_gtf: Query<&GlobalTransform, With<CheckPoint>>,
+    q_checkpoint: Query<(&GlobalTransform, &CheckPoint)>,
 ) {
-    if !keys.just_pressed(KeyCode::KeyR) {
+    let reset_level = actions.just_pressed(Action::ResetLevel);
+
+    if !reset_level && !actions.just_pressed(Action::ResetCheckpoint) {
         return;
     }
 
-    if keys.pressed(KeyCode::ShiftLeft) {
-        history.0.clear();
+    if reset_level {
+        history.checkpoints.clear();
+        ew_reset.write(LevelReset);
     };
 
-    let spawn_point = history.last(q_gtf);
+    let spawn_point = history.last(q_checkpoint);
 
-    ew.write(Respawn::<LogicalPlayer>::new(spawn_point));
+    ew.write(Respawn::<LogicalPlayer>::new(
+        spawn_point,
+        RespawnCause::Reset,
+    ));
 }
@@ -0,0 +1,123 @@
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fmt,
+    num::NonZeroUsize,
+};
+
+use bevy::prelude::*;
+use bevy_fps_controller::controller::*;
+
+use crate::prelude::*;
+
+pub struct IntegrityPlugin;
+
+impl Plugin for IntegrityPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(RunIntegrity::default())
+            .add_systems(OnEnter(AppState::InGame), reset_integrity)
+            .add_systems(Update, detect_violations.in_set(GameplaySet));
+    }
+}
+
+/// Something that makes a time incomparable with fair runs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Violation {
+    Noclip,
+    LevelSkip,
+    Timescale,
+    Practice,
+    Debug,
+    Cvars,
+}
+
+impl fmt::Display for Violation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Violation::Noclip => "noclip",
+            Violation::LevelSkip => "level skip",
+            Violation::Timescale => "timescale",
+            Violation::Practice => "practice",
+            Violation::Debug => "debug",
+            Violation::Cvars => "cvars",
+        })
+    }
+}
+
+/// Violations of the current run. Invalid levels and runs are neither saved nor compared with
+/// personal bests.
+#[derive(Resource, Debug, Default)]
+pub struct RunIntegrity {
+    levels: BTreeMap<NonZeroUsize, BTreeSet<Violation>>,
+    run: BTreeSet<Violation>,
+}
+
+impl RunIntegrity {
+    pub fn invalidate_level(&mut self, level: NonZeroUsize, violation: Violation) {
+        if self.levels.entry(level).or_default().insert(violation) {
+            info!("Level {level} is invalid: {violation}");
+        }
+    }
+
+    /// For violations that don't belong to a single level, like skipping levels.
+    pub fn invalidate_run(&mut self, violation: Violation) {
+        if self.run.insert(violation) {
+            info!("Run is invalid: {violation}");
+        }
+    }
+
+    pub fn is_level_valid(&self, level: NonZeroUsize) -> bool {
+        self.levels.get(&level).is_none_or(|x| x.is_empty())
+    }
+
+    pub fn is_run_valid(&self) -> bool {
+        self.run.is_empty() && self.levels.values().all(|x| x.is_empty())
+    }
+
+    pub fn level_violations(&self, level: NonZeroUsize) -> impl Iterator<Item = Violation> {
+        self.levels.get(&level).into_iter().flatten().copied()
+    }
+
+    pub fn run_violations(&self) -> impl Iterator<Item = Violation> {
+        let levels = self.levels.values().flatten().copied();
+        self.run
+            .iter()
+            .copied()
+            .chain(levels)
+            .collect::<BTreeSet<_>>()
+            .into_iter()
+    }
+}
+
+fn reset_integrity(mut integrity: ResMut<RunIntegrity>) {
+    *integrity = default();
+}
+
+fn detect_violations(
+    mut integrity: ResMut<RunIntegrity>,
+    q_controller: Query<&FpsController, With<LogicalPlayer>>,
+    time: Res<Time<Virtual>>,
+    debug_state: Res<State<DebugState>>,
+    current_level: Res<CurrentLevel>,
+    cvars: Res<Cvars>,
+) {
+    let level = current_level.get();
+
+    if q_controller
+        .iter()
+        .any(|x| matches!(x.move_mode, MoveMode::Noclip))
+    {
+        integrity.invalidate_level(level, Violation::Noclip);
+    }
+
+    if time.relative_speed() != 1. {
+        integrity.invalidate_level(level, Violation::Timescale);
+    }
+
+    if *debug_state.get() == DebugState::Enabled {
+        integrity.invalidate_level(level, Violation::Debug);
+    }
+
+    if !cvars.is_default() {
+        integrity.invalidate_level(level, Violation::Cvars);
+    }
+}
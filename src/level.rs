@@ -0,0 +1,160 @@
+use std::num::NonZeroUsize;
+
+use bevy::{
+    asset::{AssetLoader, LoadContext, io::Reader},
+    prelude::*,
+};
+use serde::{Deserialize, Deserializer};
+
+use crate::core::*;
+
+const LEVEL_MANIFEST_PATH: &str = "levels.ron";
+const FALLBACK_OUT_OF_BOUNDS_HEIGHT: f32 = 95.;
+
+pub struct LevelPlugin;
+
+impl Plugin for LevelPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_asset::<LevelManifest>()
+            .register_asset_loader(LevelManifestLoader)
+            .add_systems(Startup, load_manifest)
+            .add_systems(
+                Update,
+                insert_levels
+                    .run_if(in_state(AppState::Loading))
+                    .run_if(not(resource_exists::<Levels>)),
+            );
+    }
+}
+
+/// A list of levels in the order they are played, loaded from `assets/levels.ron`.
+#[derive(Asset, TypePath, Deserialize, Debug)]
+pub struct LevelManifest {
+    pub levels: Vec<LevelInfo>,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct LevelInfo {
+    pub scene: String,
+    pub skybox: String,
+    pub skybox_brightness: f32,
+    #[serde(deserialize_with = "hex_color")]
+    pub water_deep_color: Color,
+    #[serde(deserialize_with = "hex_color")]
+    pub fx_color: Color,
+    /// Vertical distance from the last spawn point that counts as out of bounds.
+    #[serde(default)]
+    pub out_of_bounds_height: Option<f32>,
+    #[serde(default)]
+    pub music: Option<LevelSound>,
+    /// Loops played along with the music.
+    #[serde(default)]
+    pub ambient: Vec<LevelSound>,
+}
+
+impl LevelInfo {
+    /// Levels with [`KillZone`]s only go out of bounds when they set a height, others fall back to
+    /// a fixed one.
+    pub fn out_of_bounds_limit(&self, has_kill_zones: bool) -> Option<f32> {
+        match has_kill_zones {
+            true => self.out_of_bounds_height,
+            false => Some(
+                self.out_of_bounds_height
+                    .unwrap_or(FALLBACK_OUT_OF_BOUNDS_HEIGHT),
+            ),
+        }
+    }
+
+    pub fn sounds(&self) -> impl Iterator<Item = &LevelSound> {
+        self.music.iter().chain(&self.ambient)
+    }
+}
+
+/// A looping sound of a level, `sound` is the path in the assets.
+#[derive(Deserialize, Debug, Clone)]
+pub struct LevelSound {
+    pub sound: String,
+    #[serde(default = "default_volume")]
+    pub volume: f32,
+}
+
+fn default_volume() -> f32 {
+    1.
+}
+
+fn hex_color<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Color, D::Error> {
+    let hex = String::deserialize(deserializer)?;
+    Srgba::hex(&hex)
+        .map(Color::from)
+        .map_err(serde::de::Error::custom)
+}
+
+#[derive(Default, TypePath)]
+struct LevelManifestLoader;
+
+impl AssetLoader for LevelManifestLoader {
+    type Asset = LevelManifest;
+    type Settings = ();
+    type Error = BevyError;
+
+    async fn load(
+        &self,
+        reader: &mut dyn Reader,
+        _settings: &(),
+        _load_context: &mut LoadContext<'_>,
+    ) -> Result<Self::Asset, Self::Error> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
+        let manifest = ron::de::from_bytes::<LevelManifest>(&bytes)?;
+
+        if manifest.levels.is_empty() {
+            return Err("The level manifest needs at least one level!".into());
+        }
+
+        Ok(manifest)
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["ron"]
+    }
+}
+
+#[derive(Resource)]
+struct LevelManifestHandle(Handle<LevelManifest>);
+
+/// The levels of the loaded [`LevelManifest`], available once loading is done.
+#[derive(Resource, Debug, Clone)]
+pub struct Levels(pub Vec<LevelInfo>);
+
+impl Levels {
+    pub fn get(&self, level: NonZeroUsize) -> &LevelInfo {
+        &self.0[level.get() - 1]
+    }
+
+    pub fn count(&self) -> usize {
+        self.0.len()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &LevelInfo> {
+        self.0.iter()
+    }
+}
+
+fn load_manifest(mut cmd: Commands, assets: Res<AssetServer>, mut loading: ResMut<AssetsLoading>) {
+    let manifest: Handle<LevelManifest> = assets.load(LEVEL_MANIFEST_PATH);
+    loading.0.push(manifest.clone().into());
+
+    cmd.insert_resource(LevelManifestHandle(manifest));
+}
+
+fn insert_levels(
+    mut cmd: Commands,
+    handle: Res<LevelManifestHandle>,
+    manifests: Res<Assets<LevelManifest>>,
+) {
+    let Some(manifest) = manifests.get(&handle.0) else {
+        return;
+    };
+
+    cmd.insert_resource(Levels(manifest.levels.clone()));
+}
@@ -0,0 +1,309 @@
+use std::{
+    io::Write,
+    mem,
+    net::{TcpStream, ToSocketAddrs},
+    num::NonZeroUsize,
+    sync::mpsc::{Sender, channel},
+    thread,
+    time::Duration,
+};
+
+use bevy::prelude::*;
+use bevy_console::*;
+use clap::Parser;
+
+use crate::prelude::*;
+
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// Autosplitting through the LiveSplit Server text protocol, see
+/// <https://github.com/LiveSplit/LiveSplit.Server> for the commands.
+pub struct LiveSplitPlugin;
+
+impl Plugin for LiveSplitPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(LiveSplitSettings::default())
+            .add_console_command::<LiveSplitCommand, _>(livesplit)
+            .add_systems(
+                Update,
+                (
+                    connect.run_if(resource_changed::<LiveSplitSettings>),
+                    (start_level, split, reset)
+                        .chain()
+                        .run_if(resource_exists::<LiveSplitConnection>),
+                )
+                    .chain(),
+            )
+            .add_systems(
+                OnEnter(AppState::InGame),
+                start_run.run_if(resource_exists::<LiveSplitConnection>),
+            )
+            .add_systems(
+                OnEnter(PausedState::Paused),
+                pause
+                    .run_if(in_state(AppState::InGame))
+                    .run_if(resource_exists::<LiveSplitConnection>),
+            )
+            .add_systems(
+                OnEnter(PausedState::Running),
+                resume
+                    .run_if(in_state(AppState::InGame))
+                    .run_if(resource_exists::<LiveSplitConnection>),
+            );
+    }
+}
+
+/// Where the LiveSplit Server component listens, disabled by default.
+#[derive(Resource, Debug)]
+pub struct LiveSplitSettings {
+    pub enabled: bool,
+    pub address: String,
+}
+
+impl Default for LiveSplitSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            address: String::from("127.0.0.1:16834"),
+        }
+    }
+}
+
+/// Sends commands from a background thread, so a missing LiveSplit never stalls a frame.
+#[derive(Resource)]
+struct LiveSplitConnection {
+    sender: Sender<String>,
+    /// Checkpoint splits of the current level attempt, taken back when the level is reset.
+    level_splits: usize,
+}
+
+impl LiveSplitConnection {
+    fn open(address: String) -> Self {
+        let (tx, rx) = channel::<String>();
+
+        thread::spawn(move || {
+            let mut stream = None;
+
+            for command in rx {
+                if stream.is_none() {
+                    stream = match connect_to(&address) {
+                        Ok(x) => Some(x),
+                        Err(e) => {
+                            warn!("Failed to connect to LiveSplit at {address}: {e}");
+                            continue;
+                        }
+                    };
+                }
+
+                if let Some(x) = &mut stream
+                    && let Err(e) = x.write_all(format!("{command}\r\n").as_bytes())
+                {
+                    warn!("Failed to send {command} to LiveSplit: {e}");
+                    stream = None;
+                }
+            }
+        });
+
+        Self {
+            sender: tx,
+            level_splits: 0,
+        }
+    }
+
+    fn send(&self, command: impl Into<String>) {
+        // The thread only stops once the connection is dropped.
+        let _ = self.sender.send(command.into());
+    }
+
+    fn set_game_time(&self, time: Duration) {
+        let millis = time.as_millis();
+        self.send(format!(
+            "setgametime {}:{:02}:{:02}.{:03}",
+            millis / 3_600_000,
+            millis / 60_000 % 60,
+            millis / 1000 % 60,
+            millis % 1000
+        ));
+    }
+}
+
+fn connect_to(address: &str) -> Result<TcpStream, BevyError> {
+    let address = address
+        .to_socket_addrs()?
+        .next()
+        .ok_or("The address did not resolve!")?;
+
+    let stream = TcpStream::connect_timeout(&address, CONNECT_TIMEOUT)?;
+    stream.set_nodelay(true)?;
+    Ok(stream)
+}
+
+fn connect(mut cmd: Commands, settings: Res<LiveSplitSettings>) {
+    match settings.enabled {
+        true => cmd.insert_resource(LiveSplitConnection::open(settings.address.clone())),
+        false => cmd.remove_resource::<LiveSplitConnection>(),
+    }
+}
+
+/// Game time of the run up to `level_time` into `level`.
+fn game_time(run_duration: &RunDuration, level: usize, level_time: Duration) -> Duration {
+    run_duration.results[..level - 1].iter().sum::<Duration>() + level_time
+}
+
+fn start_run(mut connection: ResMut<LiveSplitConnection>) {
+    connection.level_splits = 0;
+    connection.send("reset");
+    connection.send("starttimer");
+}
+
+fn start_level(mut er: MessageReader<SpawnLevel>, mut connection: ResMut<LiveSplitConnection>) {
+    for _ in er.read() {
+        connection.level_splits = 0;
+
+        // Ignored by LiveSplit while the timer is already running.
+        connection.send("starttimer");
+    }
+}
+
+fn split(
+    mut er_checkpoint: MessageReader<CheckpointReached>,
+    mut er_finished: MessageReader<LevelFinished>,
+    mut connection: ResMut<LiveSplitConnection>,
+    run_duration: Res<RunDuration>,
+    current_level: Res<CurrentLevel>,
+) {
+    for CheckpointReached(split) in er_checkpoint.read() {
+        connection.set_game_time(game_time(
+            &run_duration,
+            current_level.get().get(),
+            split.time,
+        ));
+        connection.send("split");
+        connection.level_splits += 1;
+    }
+
+    for LevelFinished { level, .. } in er_finished.read() {
+        connection.set_game_time(game_time(
+            &run_duration,
+            level.get(),
+            run_duration.results[level.get() - 1],
+        ));
+        connection.send("split");
+        connection.level_splits = 0;
+    }
+}
+
+/// Restarts the LiveSplit run on the first level. Later levels keep the earlier splits, only the
+/// checkpoint splits of the reset attempt are taken back.
+fn reset(
+    mut er: MessageReader<LevelReset>,
+    mut connection: ResMut<LiveSplitConnection>,
+    run_duration: Res<RunDuration>,
+    current_level: Res<CurrentLevel>,
+) {
+    for _ in er.read() {
+        let level = current_level.get();
+
+        if level == NonZeroUsize::MIN {
+            connection.level_splits = 0;
+            connection.send("reset");
+            connection.send("starttimer");
+            continue;
+        }
+
+        for _ in 0..mem::take(&mut connection.level_splits) {
+            connection.send("unsplit");
+        }
+
+        connection.set_game_time(game_time(&run_duration, level.get(), Duration::ZERO));
+    }
+}
+
+fn pause(
+    connection: Res<LiveSplitConnection>,
+    run_duration: Res<RunDuration>,
+    level_duration: Res<LevelDuration>,
+    current_level: Res<CurrentLevel>,
+) {
+    connection.set_game_time(game_time(
+        &run_duration,
+        current_level.get().get(),
+        level_duration.0.elapsed(),
+    ));
+    connection.send("pausegametime");
+}
+
+fn resume(connection: Res<LiveSplitConnection>) {
+    connection.send("unpausegametime");
+}
+
+#[derive(Parser, ConsoleCommand)]
+#[command(name = "livesplit")]
+struct LiveSplitCommand {
+    /// Host and port of the LiveSplit Server, toggles the connection if omitted.
+    #[arg(index = 1)]
+    address: Option<String>,
+}
+
+fn livesplit(mut log: ConsoleCommand<LiveSplitCommand>, mut settings: ResMut<LiveSplitSettings>) {
+    let Some(Ok(LiveSplitCommand { address })) = log.take() else {
+        return;
+    };
+
+    settings.enabled = match address {
+        Some(address) => {
+            settings.address = address;
+            true
+        }
+        None => !settings.enabled,
+    };
+
+    match settings.enabled {
+        true => reply!(log, "LiveSplit Enabled! Sending to {}.", settings.address),
+        false => reply!(log, "LiveSplit Disabled!"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        io::{BufRead, BufReader},
+        net::TcpListener,
+    };
+
+    use super::*;
+
+    #[test]
+    fn sends_commands_as_lines() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let connection = LiveSplitConnection::open(listener.local_addr().unwrap().to_string());
+
+        connection.send("starttimer");
+        connection.send("split");
+        connection.set_game_time(Duration::from_millis(3_723_456));
+        connection.send("pausegametime");
+        connection.send("reset");
+
+        let (stream, _) = listener.accept().unwrap();
+        stream
+            .set_read_timeout(Some(Duration::from_secs(5)))
+            .unwrap();
+
+        let lines = BufReader::new(stream)
+            .lines()
+            .take(5)
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+
+        assert_eq!(
+            lines,
+            [
+                "starttimer",
+                "split",
+                "setgametime 1:02:03.456",
+                "pausegametime",
+                "reset",
+            ]
+        );
+    }
+}
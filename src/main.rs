@@ -1,14 +1,28 @@
 mod audio;
+mod bindings;
 mod color;
 mod console;
 mod core;
+mod cvar;
 mod duration;
+mod gamepad;
+mod ghost;
 mod input;
+mod integrity;
+mod level;
+#[cfg(not(target_arch = "wasm32"))]
+mod livesplit;
 mod loading;
 mod particle;
 mod player;
+mod practice;
 mod prelude;
+mod replay;
+mod save;
+mod settings;
 mod state;
+mod storage;
+mod time_format;
 mod ui;
 mod world;
 
@@ -16,19 +30,35 @@ use bevy::prelude::*;
 use prelude::*;
 
 fn main() {
-    App::new()
-        .add_plugins((
-            CorePlugin,
-            StatePlugin,
-            ParticlePlugin,
-            WorldPlugin,
-            PlayerPlugin,
-            DurationPlugin,
-            InputPlugin,
-            UiPlugin,
-            ConsolePlugin,
-            AudioPlugin,
-            LoadingPlugin,
-        ))
-        .run();
+    let mut app = App::new();
+
+    app.add_plugins((
+        CorePlugin,
+        LevelPlugin,
+        StatePlugin,
+        ParticlePlugin,
+        WorldPlugin,
+        PlayerPlugin,
+        DurationPlugin,
+        InputPlugin,
+        UiPlugin,
+        ConsolePlugin,
+        AudioPlugin,
+        LoadingPlugin,
+        ReplayPlugin,
+        GhostPlugin,
+        (
+            SavePlugin,
+            PracticePlugin,
+            IntegrityPlugin,
+            GamepadPlugin,
+            SettingsPlugin,
+            CvarPlugin,
+        ),
+    ));
+
+    #[cfg(not(target_arch = "wasm32"))]
+    app.add_plugins(livesplit::LiveSplitPlugin);
+
+    app.run();
 }
@@ -1,49 +1,41 @@
 use bevy::prelude::*;
 use bevy_hanabi::{Gradient, prelude::*};
 
-use crate::{color::Resurrect64, core::*};
+use crate::{core::*, level::Levels};
 
 pub struct ParticlePlugin;
 impl Plugin for ParticlePlugin {
     fn build(&self, app: &mut App) {
         app.add_plugins(HanabiPlugin)
-            .add_systems(Startup, setup)
+            .add_systems(Update, setup.run_if(resource_added::<Levels>))
             .add_systems(Update, cleanup_timed::<ParticleEffect>);
     }
 }
 
-pub(crate) fn setup(mut cmd: Commands, mut effects: ResMut<Assets<EffectAsset>>) {
-    let checkpoint_fx: [Handle<EffectAsset>; LEVEL_COUNT] = [
-        Resurrect64::DEEP_PURPLE,
-        Resurrect64::CYAN,
-        Resurrect64::SCARLET,
-    ]
-    .iter()
-    .map(|x| {
-        effects.add(setup_checkpoint_effect(
-            x.to_linear().to_vec3(),
-            format!("checkpoint_effect_{:?}", x),
-        ))
-    })
-    .collect::<Vec<Handle<EffectAsset>>>()
-    .try_into()
-    .unwrap();
-
-    let new_level_fx: [Handle<EffectAsset>; LEVEL_COUNT] = [
-        Resurrect64::DEEP_PURPLE,
-        Resurrect64::CYAN,
-        Resurrect64::SCARLET,
-    ]
-    .iter()
-    .map(|x| {
-        effects.add(setup_new_level_effect(
-            x.to_linear().to_vec3(),
-            format!("new_level_effect_{:?}", x),
-        ))
-    })
-    .collect::<Vec<Handle<EffectAsset>>>()
-    .try_into()
-    .unwrap();
+pub(crate) fn setup(
+    mut cmd: Commands,
+    mut effects: ResMut<Assets<EffectAsset>>,
+    levels: Res<Levels>,
+) {
+    let checkpoint_fx: Vec<Handle<EffectAsset>> = levels
+        .iter()
+        .map(|x| {
+            effects.add(setup_checkpoint_effect(
+                x.fx_color.to_linear().to_vec3(),
+                format!("checkpoint_effect_{:?}", x.fx_color),
+            ))
+        })
+        .collect();
+
+    let new_level_fx: Vec<Handle<EffectAsset>> = levels
+        .iter()
+        .map(|x| {
+            effects.add(setup_new_level_effect(
+                x.fx_color.to_linear().to_vec3(),
+                format!("new_level_effect_{:?}", x.fx_color),
+            ))
+        })
+        .collect();
 
     cmd.insert_resource(ParticleEffects {
         boost_fx: effects.add(setup_boost_effect()),
@@ -1,18 +1,15 @@
-use std::f32::consts::TAU;
-
 use avian_pickup::actor::*;
 use avian3d::prelude::*;
 use bevy::{
     camera::Exposure,
     core_pipeline::tonemapping::Tonemapping,
-    light::{NotShadowCaster, NotShadowReceiver, VolumetricFog},
-    post_process::bloom::Bloom,
+    light::{NotShadowCaster, NotShadowReceiver},
     prelude::*,
     render::view::{ColorGrading, Hdr},
 };
 use bevy_fps_controller::controller::*;
 
-use crate::core::*;
+use crate::{core::*, cvar::Cvars, settings::Settings};
 
 pub struct PlayerPlugin;
 
@@ -26,13 +23,17 @@ impl Plugin for PlayerPlugin {
     }
 }
 
-fn setup(mut cmd: Commands) {
+fn setup(mut cmd: Commands, settings: Res<Settings>, cvars: Res<Cvars>) {
     // Note that we have two entities for the player
     // One is a "logical" player that handles the physics computation and collision
     // The other is a "render" player that is what is displayed to the user
     // This distinction is useful for later on if you want to add multiplayer,
     // where often time these two ideas are not exactly synced up
     let height = 3.0;
+    // Tuned from the first tick, later changes are applied by the cvars.
+    let mut controller = FpsController::default();
+    cvars.apply(&mut controller);
+
     let logical_entity = cmd
         .spawn((
             Collider::cylinder(1.0, height),
@@ -56,6 +57,7 @@ fn setup(mut cmd: Commands) {
                         CollisionLayer::Boost,
                         CollisionLayer::Checkpoint,
                         CollisionLayer::End,
+                        CollisionLayer::KillZone,
                     ],
                 ),
                 // TODO: Figure out why original dev placed sleeping here\
@@ -74,19 +76,11 @@ fn setup(mut cmd: Commands) {
             (NotShadowCaster, NotShadowReceiver),
             (
                 FpsControllerInput {
-                    pitch: -TAU / 12.0,
-                    yaw: TAU * 5.0 / 8.0,
-                    ..default()
-                },
-                FpsController {
-                    fly_speed: 50.,
-                    fast_fly_speed: 100.,
-                    air_acceleration: 20.,
-                    max_air_speed: 1000.,
-                    air_speed_cap: 10.,
-                    friction: 10.,
+                    pitch: SPAWN_FACING.pitch,
+                    yaw: SPAWN_FACING.yaw,
                     ..default()
                 },
+                controller,
             ),
             CollisionEventsEnabled,
         ))
@@ -108,18 +102,15 @@ fn setup(mut cmd: Commands) {
             affects_lightmapped_meshes: true,
         },
         ColorGrading::default(),
-        Bloom::NATURAL,
+        // Bloom and fog are added by the settings.
         Tonemapping::TonyMcMapface,
-        VolumetricFog {
-            ambient_intensity: 0.1,
-            ..default()
-        },
         Projection::Perspective(PerspectiveProjection {
-            fov: TAU / 5.0,
+            fov: settings.fov.to_radians(),
             ..default()
         }),
         Exposure::SUNLIGHT,
         RenderPlayer { logical_entity },
+        SpatialListener::new(0.5),
         Visibility::Visible,
         AvianPickupActor {
             interaction_distance: 5.,
@@ -0,0 +1,153 @@
+use std::num::NonZeroUsize;
+
+use avian3d::prelude::*;
+use bevy::prelude::*;
+use bevy_fps_controller::controller::*;
+use clap::ValueEnum;
+
+use crate::prelude::*;
+
+const MAX_SAVE_STATES: usize = 32;
+
+pub struct PracticePlugin;
+
+impl Plugin for PracticePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_message::<PracticeAction>()
+            .insert_resource(Practice::default())
+            .add_systems(OnEnter(AppState::InGame), reset_practice)
+            .add_systems(
+                Update,
+                (handle_practice_keys, handle_practice)
+                    .chain()
+                    .in_set(GameplaySet),
+            );
+    }
+}
+
+#[derive(Message, ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PracticeAction {
+    Save,
+    #[value(name = "tp")]
+    Teleport,
+    Previous,
+    Next,
+    Clear,
+}
+
+/// Snapshot of the [`LogicalPlayer`] to teleport back to.
+#[derive(Debug, Clone, Copy)]
+pub struct SaveState {
+    pub level: NonZeroUsize,
+    pub transform: Transform,
+    pub velocity: Vec3,
+    pub yaw: f32,
+    pub pitch: f32,
+    pub ground_tick: u8,
+    pub height: f32,
+}
+
+#[derive(Resource, Debug, Default)]
+pub struct Practice {
+    pub save_states: Vec<SaveState>,
+    pub selected: usize,
+}
+
+impl Practice {
+    pub fn selected(&self) -> Option<&SaveState> {
+        self.save_states.get(self.selected)
+    }
+
+    fn push(&mut self, save_state: SaveState) {
+        if self.save_states.len() == MAX_SAVE_STATES {
+            self.save_states.remove(0);
+        }
+
+        self.save_states.push(save_state);
+        self.selected = self.save_states.len() - 1;
+    }
+}
+
+fn reset_practice(mut practice: ResMut<Practice>) {
+    *practice = default();
+}
+
+fn handle_practice_keys(actions: ActionInput, mut ew: MessageWriter<PracticeAction>) {
+    for (binding, action) in [
+        (Action::PracticeSave, PracticeAction::Save),
+        (Action::PracticeTeleport, PracticeAction::Teleport),
+        (Action::PracticePrevious, PracticeAction::Previous),
+        (Action::PracticeNext, PracticeAction::Next),
+    ] {
+        if actions.just_pressed(binding) {
+            ew.write(action);
+        }
+    }
+}
+
+fn handle_practice(
+    mut er: MessageReader<PracticeAction>,
+    mut practice: ResMut<Practice>,
+    mut q_player: Query<
+        (
+            &mut Transform,
+            &mut LinearVelocity,
+            &mut FpsControllerInput,
+            &mut FpsController,
+        ),
+        With<LogicalPlayer>,
+    >,
+    current_level: Res<CurrentLevel>,
+    mut integrity: ResMut<RunIntegrity>,
+) {
+    for action in er.read() {
+        let Ok((mut transform, mut velocity, mut input, mut controller)) = q_player.single_mut()
+        else {
+            return;
+        };
+
+        let count = practice.save_states.len();
+
+        match action {
+            PracticeAction::Save => practice.push(SaveState {
+                level: current_level.get(),
+                transform: *transform,
+                velocity: velocity.0,
+                yaw: input.yaw,
+                pitch: input.pitch,
+                ground_tick: controller.ground_tick,
+                height: controller.height,
+            }),
+            PracticeAction::Teleport => {
+                let Some(save_state) = practice.selected().copied() else {
+                    continue;
+                };
+
+                if save_state.level != current_level.get() {
+                    info!("Save state is from level {}.", save_state.level);
+                    continue;
+                }
+
+                *transform = save_state.transform;
+                velocity.0 = save_state.velocity;
+                input.yaw = save_state.yaw;
+                input.pitch = save_state.pitch;
+                controller.ground_tick = save_state.ground_tick;
+                controller.height = save_state.height;
+
+                integrity.invalidate_level(save_state.level, Violation::Practice);
+            }
+            PracticeAction::Previous if count > 0 => {
+                practice.selected = (practice.selected + count - 1) % count;
+            }
+            PracticeAction::Next if count > 0 => {
+                practice.selected = (practice.selected + 1) % count;
+            }
+            PracticeAction::Previous | PracticeAction::Next => {}
+            PracticeAction::Clear => {
+                practice.save_states.clear();
+                practice.selected = 0;
+            }
+        }
+    }
+}
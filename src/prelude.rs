@@ -1,11 +1,22 @@
 pub use crate::audio::*;
+pub use crate::bindings::*;
 pub use crate::color::*;
 pub use crate::console::*;
 pub use crate::core::*;
+pub use crate::cvar::*;
 pub use crate::duration::*;
+pub use crate::gamepad::*;
+pub use crate::ghost::*;
 pub use crate::input::*;
+pub use crate::integrity::*;
+pub use crate::level::*;
 pub use crate::loading::*;
 pub use crate::particle::*;
 pub use crate::player::*;
+pub use crate::practice::*;
+pub use crate::replay::*;
+pub use crate::save::*;
+pub use crate::settings::*;
+pub use crate::time_format::*;
 pub use crate::ui::*;
 pub use crate::world::*;
@@ -0,0 +1,426 @@
+use std::{num::NonZeroUsize, time::Duration};
+
+use avian3d::prelude::*;
+use bevy::prelude::*;
+use bevy_fps_controller::controller::*;
+
+use crate::{prelude::*, storage};
+
+const MAGIC: &[u8; 4] = b"BHRP";
+const VERSION: u8 = 1;
+
+const JUMP: u8 = 1 << 0;
+const SPRINT: u8 = 1 << 1;
+const CROUCH: u8 = 1 << 2;
+const FLY: u8 = 1 << 3;
+
+pub struct ReplayPlugin;
+
+impl Plugin for ReplayPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(ReplayRecorder::default())
+            .add_systems(OnEnter(AppState::InGame), reset_recorder)
+            .add_systems(
+                FixedUpdate,
+                (
+                    save_replays.run_if(resource_exists::<Levels>),
+                    (reset_level_recording, record_replay, playback_replays)
+                        .chain()
+                        .in_set(GameplaySet),
+                )
+                    .chain(),
+            )
+            .add_systems(Update, save_pb_replays)
+            .add_systems(OnExit(AppState::InGame), cleanup::<ReplayPlayback>);
+    }
+}
+
+/// State of the [`LogicalPlayer`] for a single fixed tick.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct ReplayFrame {
+    pub level: u16,
+    pub flags: u8,
+    pub yaw: f32,
+    pub pitch: f32,
+    pub movement: Vec3,
+    pub translation: Vec3,
+    pub velocity: Vec3,
+}
+
+impl ReplayFrame {
+    pub fn facing(&self) -> Quat {
+        Quat::from_rotation_y(self.yaw)
+    }
+}
+
+/// Recorded [`ReplayFrame`]s of a level or a full run, stamped with the [`RunDuration`] results.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Replay {
+    pub level: NonZeroUsize,
+    pub results: Vec<Duration>,
+    pub frames: Vec<ReplayFrame>,
+}
+
+impl Replay {
+    pub fn level_key(level: NonZeroUsize) -> String {
+        format!("replays/level_{level}.bhr")
+    }
+
+    pub fn pb_key(level: NonZeroUsize) -> String {
+        format!("replays/level_{level}_pb.bhr")
+    }
+
+    pub fn run_key() -> String {
+        String::from("replays/run.bhr")
+    }
+
+    /// Result of the level the replay starts at.
+    pub fn result(&self) -> Option<Duration> {
+        self.results.get(self.level.get() - 1).copied()
+    }
+
+    /// Frame at `tick` into the replay, holding the last frame once it's over.
+    pub fn frame_at(&self, tick: u64) -> Option<&ReplayFrame> {
+        self.frames.get(tick as usize).or(self.frames.last())
+    }
+
+    pub fn load(key: &str) -> Result<Option<Self>, BevyError> {
+        let Some(bytes) = storage::load(key)? else {
+            return Ok(None);
+        };
+
+        Ok(Some(Self::decode(&bytes)?))
+    }
+
+    pub fn save(&self, key: &str) -> Result<(), BevyError> {
+        storage::save(key, &self.encode())
+    }
+
+    pub fn encode(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(32 + self.frames.len() * 47);
+
+        bytes.extend_from_slice(MAGIC);
+        bytes.push(VERSION);
+        bytes.extend_from_slice(&TICK_HZ.to_le_bytes());
+        bytes.extend_from_slice(&(self.level.get() as u32).to_le_bytes());
+
+        bytes.extend_from_slice(&(self.results.len() as u32).to_le_bytes());
+        for result in &self.results {
+            bytes.extend_from_slice(&(result.as_nanos() as u64).to_le_bytes());
+        }
+
+        bytes.extend_from_slice(&(self.frames.len() as u32).to_le_bytes());
+        for frame in &self.frames {
+            bytes.extend_from_slice(&frame.level.to_le_bytes());
+            bytes.push(frame.flags);
+            for x in [frame.yaw, frame.pitch]
+                .into_iter()
+                .chain(frame.movement.to_array())
+                .chain(frame.translation.to_array())
+                .chain(frame.velocity.to_array())
+            {
+                bytes.extend_from_slice(&x.to_le_bytes());
+            }
+        }
+
+        bytes
+    }
+
+    pub fn decode(bytes: &[u8]) -> Result<Self, BevyError> {
+        let mut reader = ByteReader(bytes);
+
+        if reader.take::<4>()? != *MAGIC {
+            return Err("Not a replay file!".into());
+        }
+
+        let [version] = reader.take::<1>()?;
+        if version != VERSION {
+            return Err(format!("Unsupported replay version {version}!").into());
+        }
+
+        let tick_hz = u16::from_le_bytes(reader.take()?);
+        if tick_hz != TICK_HZ {
+            return Err(format!("Unsupported replay tick rate {tick_hz}!").into());
+        }
+
+        let level = NonZeroUsize::new(u32::from_le_bytes(reader.take()?) as usize)
+            .ok_or("Replay level must be greater than 0!")?;
+
+        let results = (0..u32::from_le_bytes(reader.take()?))
+            .map(|_| Ok(Duration::from_nanos(u64::from_le_bytes(reader.take()?))))
+            .collect::<Result<Vec<_>, BevyError>>()?;
+
+        let frames = (0..u32::from_le_bytes(reader.take()?))
+            .map(|_| {
+                let level = u16::from_le_bytes(reader.take()?);
+                let [flags] = reader.take::<1>()?;
+                let mut floats = [0.; 11];
+                for x in &mut floats {
+                    *x = f32::from_le_bytes(reader.take()?);
+                }
+
+                Ok(ReplayFrame {
+                    level,
+                    flags,
+                    yaw: floats[0],
+                    pitch: floats[1],
+                    movement: Vec3::from_slice(&floats[2..5]),
+                    translation: Vec3::from_slice(&floats[5..8]),
+                    velocity: Vec3::from_slice(&floats[8..11]),
+                })
+            })
+            .collect::<Result<Vec<_>, BevyError>>()?;
+
+        Ok(Self {
+            level,
+            results,
+            frames,
+        })
+    }
+}
+
+struct ByteReader<'a>(&'a [u8]);
+
+impl ByteReader<'_> {
+    fn take<const N: usize>(&mut self) -> Result<[u8; N], BevyError> {
+        let Some((head, tail)) = self.0.split_first_chunk::<N>() else {
+            return Err("Unexpected end of replay file!".into());
+        };
+        self.0 = tail;
+        Ok(*head)
+    }
+}
+
+#[derive(Resource, Default)]
+pub struct ReplayRecorder {
+    level: Vec<ReplayFrame>,
+    run: Vec<ReplayFrame>,
+    /// Finished level replays, waiting for [`RunRecords`] to tell whether they are personal bests.
+    finished: Vec<Replay>,
+}
+
+/// Drives a non-physical entity through the frames of a [`Replay`].
+#[derive(Component)]
+pub struct ReplayPlayback {
+    pub replay: Replay,
+    pub tick: usize,
+}
+
+pub fn spawn_replay_playback(
+    cmd: &mut Commands,
+    meshes: &mut Assets<Mesh>,
+    materials: &mut Assets<StandardMaterial>,
+    replay: Replay,
+) -> Entity {
+    cmd.spawn((
+        Mesh3d(meshes.add(Cylinder::new(1.0, 3.0))),
+        MeshMaterial3d(materials.add(StandardMaterial {
+            base_color: Resurrect64::LIGHT_BLUE,
+            unlit: true,
+            ..default()
+        })),
+        Transform::default(),
+        Visibility::Hidden,
+        ReplayPlayback { replay, tick: 0 },
+    ))
+    .id()
+}
+
+fn reset_recorder(mut recorder: ResMut<ReplayRecorder>) {
+    recorder.level.clear();
+    recorder.run.clear();
+    recorder.finished.clear();
+}
+
+fn reset_level_recording(
+    mut recorder: ResMut<ReplayRecorder>,
+    mut er_respawn: MessageReader<Respawn<LogicalPlayer>>,
+    mut er_level: MessageReader<SpawnLevel>,
+    history: Res<History>,
+) {
+    for _ in er_level.read() {
+        recorder.level.clear();
+    }
+
+    if !history.empty() {
+        return;
+    }
+
+    for _ in er_respawn.read() {
+        recorder.level.clear();
+    }
+}
+
+fn record_replay(
+    mut recorder: ResMut<ReplayRecorder>,
+    q_player: Query<(&Transform, &LinearVelocity, &FpsControllerInput), With<LogicalPlayer>>,
+    current_level: Res<CurrentLevel>,
+) {
+    for (transform, velocity, input) in &q_player {
+        let mut flags = 0;
+        for (pressed, flag) in [
+            (input.jump, JUMP),
+            (input.sprint, SPRINT),
+            (input.crouch, CROUCH),
+            (input.fly, FLY),
+        ] {
+            if pressed {
+                flags |= flag;
+            }
+        }
+
+        recorder.level.push(ReplayFrame {
+            level: current_level.get().get() as u16,
+            flags,
+            yaw: input.yaw,
+            pitch: input.pitch,
+            movement: input.movement,
+            translation: transform.translation,
+            velocity: velocity.0,
+        });
+    }
+}
+
+fn save_replays(
+    mut recorder: ResMut<ReplayRecorder>,
+    mut er: MessageReader<LevelFinished>,
+    run_duration: Res<RunDuration>,
+    levels: Res<Levels>,
+) {
+    for LevelFinished { level, .. } in er.read() {
+        let frames = std::mem::take(&mut recorder.level);
+        recorder.run.extend_from_slice(&frames);
+
+        let replay = Replay {
+            level: *level,
+            results: run_duration.results.clone(),
+            frames,
+        };
+
+        if let Err(e) = replay.save(&Replay::level_key(*level)) {
+            warn!("Failed to save replay for level {level}: {e}");
+        }
+
+        recorder.finished.push(replay);
+
+        if level.get() != levels.count() {
+            continue;
+        }
+
+        let replay = Replay {
+            level: NonZeroUsize::MIN,
+            results: run_duration.results.clone(),
+            frames: std::mem::take(&mut recorder.run),
+        };
+
+        if let Err(e) = replay.save(&Replay::run_key()) {
+            warn!("Failed to save run replay: {e}");
+        }
+    }
+}
+
+/// Saves the finished level replays that [`RunRecords`] counts as personal bests, so the ghost
+/// always races the listed personal best.
+fn save_pb_replays(
+    mut recorder: ResMut<ReplayRecorder>,
+    run_records: Res<RunRecords>,
+    integrity: Res<RunIntegrity>,
+) {
+    recorder.finished.retain(|replay| {
+        if !integrity.is_level_valid(replay.level) {
+            return false;
+        }
+
+        let Some(record) = run_records
+            .levels
+            .get(&replay.level.get())
+            .filter(|x| replay.result() == Some(x.time))
+        else {
+            return true;
+        };
+
+        if record.is_pb()
+            && let Err(e) = replay.save(&Replay::pb_key(replay.level))
+        {
+            warn!(
+                "Failed to save personal best replay for level {}: {e}",
+                replay.level
+            );
+        }
+
+        false
+    });
+}
+
+fn playback_replays(
+    mut cmd: Commands,
+    mut q_playback: Query<(Entity, &mut ReplayPlayback, &mut Transform, &mut Visibility)>,
+    current_level: Res<CurrentLevel>,
+) {
+    for (entity, mut playback, mut transform, mut visibility) in &mut q_playback {
+        let Some(frame) = playback.replay.frames.get(playback.tick).copied() else {
+            cmd.entity(entity).despawn();
+            continue;
+        };
+
+        transform.translation = frame.translation;
+        transform.rotation = frame.facing();
+        *visibility = match frame.level as usize == current_level.get().get() {
+            true => Visibility::Visible,
+            false => Visibility::Hidden,
+        };
+
+        playback.tick += 1;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn replay() -> Replay {
+        Replay {
+            level: NonZeroUsize::new(2).unwrap(),
+            results: vec![
+                Duration::from_millis(12_345),
+                Duration::from_nanos(6_789_000_001),
+            ],
+            frames: vec![
+                ReplayFrame {
+                    level: 2,
+                    flags: JUMP | SPRINT,
+                    yaw: 1.5,
+                    pitch: -0.25,
+                    movement: Vec3::new(1., 0., -1.),
+                    translation: Vec3::new(10., 20., 30.),
+                    velocity: Vec3::new(-4., 0.5, 8.),
+                },
+                ReplayFrame::default(),
+            ],
+        }
+    }
+
+    #[test]
+    fn decodes_what_it_encodes() {
+        let replay = replay();
+
+        assert_eq!(Replay::decode(&replay.encode()).unwrap(), replay);
+    }
+
+    #[test]
+    fn rejects_truncated_replays() {
+        let bytes = replay().encode();
+
+        for len in 0..bytes.len() {
+            assert!(Replay::decode(&bytes[..len]).is_err());
+        }
+    }
+
+    #[test]
+    fn rejects_other_files() {
+        let mut bytes = replay().encode();
+        bytes[..4].copy_from_slice(b"RIFF");
+
+        assert!(Replay::decode(&bytes).is_err());
+    }
+}
@@ -0,0 +1,208 @@
+use std::{collections::BTreeMap, time::Duration};
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::{prelude::*, storage};
+
+const SAVE_KEY: &str = "save.ron";
+const LEADERBOARD_SIZE: usize = 10;
+
+pub struct SavePlugin;
+
+impl Plugin for SavePlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(SaveData::load())
+            .insert_resource(RunRecords::default())
+            .add_systems(OnEnter(AppState::InGame), reset_run_records)
+            // Runs before the game over screen is set up in the next frame.
+            .add_systems(Update, record_times.run_if(resource_exists::<Levels>));
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Record {
+    pub time: Duration,
+    /// Seconds since the unix epoch.
+    pub timestamp: u64,
+    pub player_name: String,
+    #[serde(default)]
+    pub splits: Vec<Split>,
+}
+
+impl Record {
+    pub fn split(&self, index: u32) -> Option<Duration> {
+        self.splits
+            .iter()
+            .find(|x| x.index == index)
+            .map(|x| x.time)
+    }
+}
+
+/// Best times, fastest first.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct Leaderboard(pub Vec<Record>);
+
+impl Leaderboard {
+    pub fn best(&self) -> Option<&Record> {
+        self.0.first()
+    }
+
+    pub fn insert(&mut self, record: Record) {
+        let index = self.0.partition_point(|x| x.time <= record.time);
+        self.0.insert(index, record);
+        self.0.truncate(LEADERBOARD_SIZE);
+    }
+}
+
+/// Local leaderboards of each level and of full runs, persisted across sessions.
+#[derive(Resource, Serialize, Deserialize, Debug)]
+#[serde(default)]
+pub struct SaveData {
+    pub player_name: String,
+    pub time_format: TimeFormat,
+    pub levels: BTreeMap<usize, Leaderboard>,
+    pub runs: Leaderboard,
+}
+
+impl Default for SaveData {
+    fn default() -> Self {
+        Self {
+            player_name: String::from("Player"),
+            time_format: default(),
+            levels: default(),
+            runs: default(),
+        }
+    }
+}
+
+impl SaveData {
+    pub fn load() -> Self {
+        let bytes = match storage::load(SAVE_KEY) {
+            Ok(Some(bytes)) => bytes,
+            Ok(None) => return default(),
+            Err(e) => {
+                warn!("Failed to load save data: {e}");
+                return default();
+            }
+        };
+
+        ron::de::from_bytes(&bytes).unwrap_or_else(|e| {
+            warn!("Failed to parse save data: {e}");
+            default()
+        })
+    }
+
+    pub fn save(&self) -> Result<(), BevyError> {
+        let ron = ron::ser::to_string_pretty(self, default())?;
+        storage::save(SAVE_KEY, ron.as_bytes())
+    }
+
+    /// Personal best of `level`.
+    pub fn level_pb(&self, level: usize) -> Option<&Record> {
+        self.levels.get(&level).and_then(|x| x.best())
+    }
+
+    fn record(&self, time: Duration, splits: Vec<Split>) -> Record {
+        Record {
+            time,
+            timestamp: unix_timestamp(),
+            player_name: self.player_name.clone(),
+            splits,
+        }
+    }
+}
+
+/// Comparison of a time of the current run with the personal best before it.
+#[derive(Debug, Clone, Copy)]
+pub struct RecordResult {
+    pub time: Duration,
+    pub pb: Option<Duration>,
+}
+
+impl RecordResult {
+    pub fn is_pb(&self) -> bool {
+        self.pb.is_none_or(|pb| self.time < pb)
+    }
+}
+
+#[derive(Resource, Debug, Default)]
+pub struct RunRecords {
+    pub levels: BTreeMap<usize, RecordResult>,
+    pub splits: BTreeMap<usize, Vec<RecordResult>>,
+    pub run: Option<RecordResult>,
+}
+
+fn reset_run_records(mut run_records: ResMut<RunRecords>) {
+    *run_records = default();
+}
+
+fn record_times(
+    mut er: MessageReader<LevelFinished>,
+    mut save_data: ResMut<SaveData>,
+    mut run_records: ResMut<RunRecords>,
+    run_duration: Res<RunDuration>,
+    levels: Res<Levels>,
+    integrity: Res<RunIntegrity>,
+) {
+    for LevelFinished { level, splits } in er.read() {
+        if !integrity.is_level_valid(*level) {
+            continue;
+        }
+
+        let time = run_duration.results[level.get() - 1];
+        let pb = save_data.level_pb(level.get());
+
+        run_records.levels.insert(
+            level.get(),
+            RecordResult {
+                time,
+                pb: pb.map(|x| x.time),
+            },
+        );
+        run_records.splits.insert(
+            level.get(),
+            splits
+                .iter()
+                .map(|split| RecordResult {
+                    time: split.time,
+                    pb: pb.and_then(|x| x.split(split.index)),
+                })
+                .collect(),
+        );
+
+        let record = save_data.record(time, splits.clone());
+        save_data
+            .levels
+            .entry(level.get())
+            .or_default()
+            .insert(record);
+
+        if level.get() == levels.count() && integrity.is_run_valid() {
+            let time = run_duration.results.iter().sum();
+            let record = save_data.record(time, Vec::new());
+
+            run_records.run = Some(RecordResult {
+                time,
+                pb: save_data.runs.best().map(|x| x.time),
+            });
+            save_data.runs.insert(record);
+        }
+
+        if let Err(e) = save_data.save() {
+            warn!("Failed to save save data: {e}");
+        }
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn unix_timestamp() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map_or(0, |x| x.as_secs())
+}
+
+#[cfg(target_arch = "wasm32")]
+fn unix_timestamp() -> u64 {
+    (js_sys::Date::now() / 1000.) as u64
+}
@@ -0,0 +1,152 @@
+use bevy::{light::VolumetricFog, post_process::bloom::Bloom, prelude::*, window::PresentMode};
+use bevy_dev_tools::fps_overlay::FpsOverlayConfig;
+use bevy_fps_controller::controller::*;
+use serde::{Deserialize, Serialize};
+
+use crate::{prelude::*, storage};
+
+const SETTINGS_KEY: &str = "settings.ron";
+
+/// Mouse sensitivity of the fps controller at a `mouse_sensitivity` of 1.
+const BASE_SENSITIVITY: f32 = 0.001;
+
+pub struct SettingsPlugin;
+
+impl Plugin for SettingsPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(Settings::load()).add_systems(
+            Update,
+            (
+                save_settings
+                    .run_if(resource_changed::<Settings>.and(not(resource_added::<Settings>))),
+                apply_settings.run_if(resource_changed::<Settings>),
+                apply_controller_settings.run_if(
+                    resource_changed::<Settings>.or(any_match_filter::<Added<FpsController>>),
+                ),
+                apply_camera_settings.run_if(
+                    resource_changed::<Settings>.or(any_match_filter::<Added<RenderPlayer>>),
+                ),
+                apply_light_settings.run_if(
+                    resource_changed::<Settings>.or(any_match_filter::<Added<DirectionalLight>>),
+                ),
+            ),
+        );
+    }
+}
+
+/// User preferences, persisted across sessions.
+#[derive(Resource, Serialize, Deserialize, Debug, Clone)]
+#[serde(default)]
+pub struct Settings {
+    pub mouse_sensitivity: f32,
+    /// Vertical field of view in degrees.
+    pub fov: f32,
+    pub mix: AudioMix,
+    /// Share of the music volume kept in the pause menu, the music stops at 0.
+    pub paused_music_volume: f32,
+    pub bloom: bool,
+    pub fog: bool,
+    pub shadows: bool,
+    pub present_mode: PresentMode,
+    pub fps_overlay: bool,
+    pub scroll_jump: ScrollJump,
+    pub sticks: StickSettings,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            mouse_sensitivity: 1.,
+            fov: 72.,
+            mix: default(),
+            paused_music_volume: 0.3,
+            bloom: true,
+            fog: true,
+            shadows: true,
+            present_mode: PresentMode::AutoVsync,
+            fps_overlay: false,
+            scroll_jump: default(),
+            sticks: default(),
+        }
+    }
+}
+
+impl Settings {
+    pub fn load() -> Self {
+        let bytes = match storage::load_config(SETTINGS_KEY) {
+            Ok(Some(bytes)) => bytes,
+            Ok(None) => return default(),
+            Err(e) => {
+                warn!("Failed to load settings: {e}");
+                return default();
+            }
+        };
+
+        ron::de::from_bytes(&bytes).unwrap_or_else(|e| {
+            warn!("Failed to parse settings: {e}");
+            default()
+        })
+    }
+
+    pub fn save(&self) -> Result<(), BevyError> {
+        let ron = ron::ser::to_string_pretty(self, default())?;
+        storage::save_config(SETTINGS_KEY, ron.as_bytes())
+    }
+}
+
+fn save_settings(settings: Res<Settings>) {
+    if let Err(e) = settings.save() {
+        warn!("Failed to save settings: {e}");
+    }
+}
+
+fn apply_settings(
+    settings: Res<Settings>,
+    mut q_window: Query<&mut Window>,
+    mut overlay: ResMut<FpsOverlayConfig>,
+) {
+    for mut window in &mut q_window {
+        window.present_mode = settings.present_mode;
+    }
+
+    overlay.enabled = settings.fps_overlay;
+}
+
+fn apply_controller_settings(settings: Res<Settings>, mut q_controller: Query<&mut FpsController>) {
+    for mut controller in &mut q_controller {
+        controller.sensitivity = BASE_SENSITIVITY * settings.mouse_sensitivity;
+    }
+}
+
+fn apply_camera_settings(
+    mut cmd: Commands,
+    settings: Res<Settings>,
+    mut q_camera: Query<(Entity, &mut Projection), With<RenderPlayer>>,
+) {
+    for (camera, mut projection) in &mut q_camera {
+        if let Projection::Perspective(perspective) = projection.as_mut() {
+            perspective.fov = settings.fov.to_radians();
+        }
+
+        let mut camera = cmd.entity(camera);
+
+        match settings.bloom {
+            true => camera.insert(Bloom::NATURAL),
+            false => camera.remove::<Bloom>(),
+        };
+
+        match settings.fog {
+            true => camera.insert(VolumetricFog {
+                ambient_intensity: 0.1,
+                ..default()
+            }),
+            false => camera.remove::<VolumetricFog>(),
+        };
+    }
+}
+
+fn apply_light_settings(settings: Res<Settings>, mut q_light: Query<&mut DirectionalLight>) {
+    for mut light in &mut q_light {
+        light.shadows_enabled = settings.shadows;
+    }
+}
@@ -0,0 +1,102 @@
+use bevy::prelude::*;
+
+const APP_NAME: &str = "bevy_hop";
+
+#[cfg(not(target_arch = "wasm32"))]
+fn data_path(key: &str) -> Result<std::path::PathBuf, BevyError> {
+    let dir = dirs::data_dir().ok_or("No data directory available on this platform!")?;
+    Ok(dir.join(APP_NAME).join(key))
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn config_path(key: &str) -> Result<std::path::PathBuf, BevyError> {
+    let dir = dirs::config_dir().ok_or("No config directory available on this platform!")?;
+    Ok(dir.join(APP_NAME).join(key))
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn write(path: std::path::PathBuf, bytes: &[u8]) -> Result<(), BevyError> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::write(path, bytes)?;
+    Ok(())
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn read(path: std::path::PathBuf) -> Result<Option<Vec<u8>>, BevyError> {
+    match std::fs::read(path) {
+        Ok(bytes) => Ok(Some(bytes)),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// Persists `bytes` across sessions, as a file in the platform data directory natively and as a
+/// base64 encoded local storage entry on wasm.
+#[cfg(not(target_arch = "wasm32"))]
+pub fn save(key: &str, bytes: &[u8]) -> Result<(), BevyError> {
+    write(data_path(key)?, bytes)
+}
+
+/// Reads what [`save`] persisted under `key`, `None` if nothing was saved yet.
+#[cfg(not(target_arch = "wasm32"))]
+pub fn load(key: &str) -> Result<Option<Vec<u8>>, BevyError> {
+    read(data_path(key)?)
+}
+
+/// Like [`save`], for preferences like the settings and bindings.
+#[cfg(not(target_arch = "wasm32"))]
+pub fn save_config(key: &str, bytes: &[u8]) -> Result<(), BevyError> {
+    write(config_path(key)?, bytes)
+}
+
+/// Like [`load`], for preferences like the settings and bindings.
+#[cfg(not(target_arch = "wasm32"))]
+pub fn load_config(key: &str) -> Result<Option<Vec<u8>>, BevyError> {
+    read(config_path(key)?)
+}
+
+#[cfg(target_arch = "wasm32")]
+fn local_storage() -> Result<web_sys::Storage, BevyError> {
+    web_sys::window()
+        .and_then(|x| x.local_storage().ok().flatten())
+        .ok_or_else(|| "Local storage is not available!".into())
+}
+
+/// Persists `bytes` across sessions, as a file in the platform data directory natively and as a
+/// base64 encoded local storage entry on wasm.
+#[cfg(target_arch = "wasm32")]
+pub fn save(key: &str, bytes: &[u8]) -> Result<(), BevyError> {
+    use base64::prelude::*;
+
+    local_storage()?
+        .set_item(&format!("{APP_NAME}/{key}"), &BASE64_STANDARD.encode(bytes))
+        .map_err(|_| format!("Failed to write {key} to local storage!"))?;
+    Ok(())
+}
+
+/// Reads what [`save`] persisted under `key`, `None` if nothing was saved yet.
+#[cfg(target_arch = "wasm32")]
+pub fn load(key: &str) -> Result<Option<Vec<u8>>, BevyError> {
+    use base64::prelude::*;
+
+    let item = local_storage()?
+        .get_item(&format!("{APP_NAME}/{key}"))
+        .map_err(|_| format!("Failed to read {key} from local storage!"))?;
+
+    match item {
+        Some(x) => Ok(Some(BASE64_STANDARD.decode(x)?)),
+        None => Ok(None),
+    }
+}
+
+#[cfg(target_arch = "wasm32")]
+pub fn save_config(key: &str, bytes: &[u8]) -> Result<(), BevyError> {
+    save(key, bytes)
+}
+
+#[cfg(target_arch = "wasm32")]
+pub fn load_config(key: &str) -> Result<Option<Vec<u8>>, BevyError> {
+    load(key)
+}
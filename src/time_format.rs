@@ -0,0 +1,108 @@
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+use crate::core::TICK_HZ;
+
+/// How times are displayed, truncated to the shown precision so they never round up.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TimeFormat {
+    /// `MM:SS.mmm`
+    #[default]
+    Millis,
+    /// `MM:SS.cc`
+    Centis,
+    /// `MM:SS.mmm` followed by the number of fixed ticks.
+    Ticks,
+}
+
+impl TimeFormat {
+    pub const ALL: [TimeFormat; 3] = [TimeFormat::Millis, TimeFormat::Centis, TimeFormat::Ticks];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            TimeFormat::Millis => "millis",
+            TimeFormat::Centis => "centis",
+            TimeFormat::Ticks => "ticks",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|x| x.name() == name)
+    }
+
+    /// Hours are only shown once a time reaches them.
+    pub fn format(&self, duration: Duration) -> String {
+        let millis = duration.as_millis();
+        let h = millis / 3_600_000;
+        let m = millis / 60_000 % 60;
+        let s = millis / 1000 % 60;
+        let ms = millis % 1000;
+
+        let clock = match h {
+            0 => format!("{m:02}:{s:02}"),
+            _ => format!("{h}:{m:02}:{s:02}"),
+        };
+
+        match self {
+            TimeFormat::Millis => format!("{clock}.{ms:03}"),
+            TimeFormat::Centis => format!("{clock}.{:02}", ms / 10),
+            TimeFormat::Ticks => format!("{clock}.{ms:03} ({}t)", ticks(duration)),
+        }
+    }
+
+    /// Signed difference of `time` to `reference`, negative when `time` is faster.
+    pub fn format_delta(&self, time: Duration, reference: Duration) -> String {
+        match time < reference {
+            true => format!("-{}", self.format(reference - time)),
+            false => format!("+{}", self.format(time - reference)),
+        }
+    }
+}
+
+fn ticks(duration: Duration) -> u128 {
+    duration.as_nanos() * TICK_HZ as u128 / 1_000_000_000
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn truncates_instead_of_rounding() {
+        let time = Duration::from_micros(59_999_600);
+
+        assert_eq!(TimeFormat::Millis.format(time), "00:59.999");
+        assert_eq!(TimeFormat::Centis.format(time), "00:59.99");
+    }
+
+    #[test]
+    fn shows_hours_once_reached() {
+        assert_eq!(
+            TimeFormat::Millis.format(Duration::from_millis(3_599_999)),
+            "59:59.999"
+        );
+        assert_eq!(
+            TimeFormat::Millis.format(Duration::from_secs(3600)),
+            "1:00:00.000"
+        );
+    }
+
+    #[test]
+    fn signs_deltas() {
+        let reference = Duration::from_secs(10);
+
+        assert_eq!(
+            TimeFormat::Millis.format_delta(Duration::from_millis(9_750), reference),
+            "-00:00.250"
+        );
+        assert_eq!(
+            TimeFormat::Millis.format_delta(Duration::from_millis(10_250), reference),
+            "+00:00.250"
+        );
+        assert_eq!(
+            TimeFormat::Millis.format_delta(reference, reference),
+            "+00:00.000"
+        );
+    }
+}
@@ -33,6 +33,15 @@ pub(super) struct LevelDurationText;
 #[derive(Component)]
 pub(super) struct RunDurationText;
 
+#[derive(Component)]
+pub(super) struct RealTimeText;
+
+#[derive(Component)]
+pub(super) struct SplitDeltaContainer;
+
+#[derive(Component)]
+pub(super) struct SplitDeltaCard;
+
 pub(super) fn get_header(
     text_resource: &Res<TextResource>,
 ) -> (impl Bundle, SpawnRelatedBundle<ChildOf, Spawn<impl Bundle>>) {
@@ -66,15 +75,23 @@ pub(super) fn setup_font(
 }
 
 pub(super) fn button_system(
+    mut cmd: Commands,
     mut interaction_query: Query<
         (&Interaction, &mut BackgroundColor),
         (Changed<Interaction>, With<Button>),
     >,
+    bank: Res<SoundBank>,
 ) {
     for (interaction, mut color) in &mut interaction_query {
         match *interaction {
             Interaction::Pressed => {
                 *color = PRESSED_BUTTON.into();
+
+                cmd.spawn((
+                    AudioPlayer::new(bank.get("boost")),
+                    PlaybackSettings::DESPAWN,
+                    BusSound::new(AudioBus::Ui, 0.1),
+                ));
             }
             Interaction::Hovered => {
                 *color = HOVERED_BUTTON.into();
@@ -85,10 +102,3 @@ pub(super) fn button_system(
         }
     }
 }
-
-pub(super) fn format_duration(secs: f32) -> String {
-    let h = secs / 3600.;
-    let m = (secs % 3600.) / 60.;
-    let s = secs % 60.;
-    format!("{:02.0}:{:02.0}:{:02.0}", h, m, s)
-}
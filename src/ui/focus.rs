@@ -0,0 +1,116 @@
+use bevy::{prelude::*, ui::UiGlobalTransform};
+
+use crate::bindings::*;
+
+use super::*;
+
+pub struct FocusPlugin;
+
+impl Plugin for FocusPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(MenuFocus::default())
+            .add_observer(activate_on_click)
+            .add_systems(
+                Update,
+                (
+                    navigate_focus,
+                    highlight_focus.run_if(resource_changed::<MenuFocus>),
+                )
+                    .chain()
+                    .after(button_system),
+            );
+    }
+}
+
+/// A menu button was pressed, either clicked or confirmed while focused.
+#[derive(EntityEvent, Debug, Clone, Copy)]
+pub struct MenuActivate {
+    pub entity: Entity,
+}
+
+/// Button selected with the menu actions, so menus can be used without a mouse.
+#[derive(Resource, Debug, Default)]
+struct MenuFocus(Option<Entity>);
+
+fn activate_on_click(
+    click: On<Pointer<Click>>,
+    mut cmd: Commands,
+    q_button: Query<(), With<Button>>,
+) {
+    // Clicks on the text of a button bubble up to it.
+    if q_button.contains(click.entity) {
+        cmd.trigger(MenuActivate {
+            entity: click.entity,
+        });
+    }
+}
+
+fn navigate_focus(
+    mut cmd: Commands,
+    actions: ActionInput,
+    mut focus: ResMut<MenuFocus>,
+    q_button: Query<(Entity, &UiGlobalTransform, &InheritedVisibility), With<Button>>,
+) {
+    let step = match (
+        actions.just_pressed(Action::MenuUp) || actions.just_pressed(Action::MenuLeft),
+        actions.just_pressed(Action::MenuDown) || actions.just_pressed(Action::MenuRight),
+    ) {
+        (true, false) => -1,
+        (false, true) => 1,
+        _ => 0,
+    };
+    let confirm = actions.just_pressed(Action::MenuConfirm);
+
+    if step == 0 && !confirm {
+        return;
+    }
+
+    // Reading order, so the focus follows the layout of the menu.
+    let mut buttons = q_button
+        .iter()
+        .filter(|(_, _, visibility)| visibility.get())
+        .map(|(entity, transform, _)| (entity, transform.translation))
+        .collect::<Vec<_>>();
+    buttons.sort_by(|(_, a), (_, b)| a.y.total_cmp(&b.y).then(a.x.total_cmp(&b.x)));
+
+    let Some(current) = focus
+        .0
+        .and_then(|x| buttons.iter().position(|(entity, _)| *entity == x))
+    else {
+        // The first press only focuses the first button.
+        focus.0 = buttons.first().map(|(entity, _)| *entity);
+        return;
+    };
+
+    if step != 0 {
+        let count = buttons.len() as isize;
+        let next = (current as isize + step).rem_euclid(count) as usize;
+        focus.0 = Some(buttons[next].0);
+        return;
+    }
+
+    cmd.trigger(MenuActivate {
+        entity: buttons[current].0,
+    });
+}
+
+fn highlight_focus(
+    focus: Res<MenuFocus>,
+    mut previous: Local<Option<Entity>>,
+    mut q_button: Query<(&Interaction, &mut BackgroundColor), With<Button>>,
+) {
+    if let Some(entity) = previous.take()
+        && let Ok((interaction, mut color)) = q_button.get_mut(entity)
+        && *interaction == Interaction::None
+    {
+        *color = NORMAL_BUTTON.into();
+    }
+
+    if let Some(entity) = focus.0
+        && let Ok((_, mut color)) = q_button.get_mut(entity)
+    {
+        *color = HOVERED_BUTTON.into();
+    }
+
+    *previous = focus.0;
+}
@@ -1,6 +1,11 @@
-use bevy::{ecs::relationship::RelatedSpawnerCommands, prelude::*};
+use std::{num::NonZeroUsize, time::Duration};
 
-use crate::core::*;
+use bevy::{
+    ecs::{relationship::RelatedSpawnerCommands, system::SystemParam},
+    prelude::*,
+};
+
+use crate::{core::*, integrity::*, save::*};
 
 use super::*;
 
@@ -22,12 +27,23 @@ impl Plugin for GameOverPlugin {
     }
 }
 
-fn setup(mut cmd: Commands, text_resource: Res<TextResource>, run_duration: Res<RunDuration>) {
+/// Results of the finished run.
+#[derive(SystemParam)]
+struct Summary<'w> {
+    run_duration: Res<'w, RunDuration>,
+    run_records: Res<'w, RunRecords>,
+    real_time: Res<'w, RealTimeDuration>,
+    attempts: Res<'w, Attempts>,
+    integrity: Res<'w, RunIntegrity>,
+    save_data: Res<'w, SaveData>,
+}
+
+fn setup(mut cmd: Commands, text_resource: Res<TextResource>, summary: Summary) {
     layout(&mut cmd).with_children(|cmd| {
         cmd.spawn(NodeBuilder::new().get_card())
             .with_children(|cmd| {
                 header(cmd, &text_resource);
-                content(cmd, &text_resource, &run_duration);
+                content(cmd, &text_resource, &summary);
                 actions(cmd, &text_resource);
             });
     });
@@ -66,11 +82,9 @@ fn actions(cmd: &mut RelatedSpawnerCommands<'_, ChildOf>, text_resource: &Res<Te
                     text_resource.get_button_text_props()
                 )],
             ))
-            .observe(
-                |_: On<Pointer<Click>>, mut ns: ResMut<NextState<AppState>>| {
-                    ns.set(AppState::MainMenu);
-                },
-            );
+            .observe(|_: On<MenuActivate>, mut ns: ResMut<NextState<AppState>>| {
+                ns.set(AppState::MainMenu);
+            });
         });
 
     #[cfg(not(target_arch = "wasm32"))]
@@ -78,7 +92,7 @@ fn actions(cmd: &mut RelatedSpawnerCommands<'_, ChildOf>, text_resource: &Res<Te
         NodeBuilder::new().get_button(),
         children![(Text::new("Quit"), text_resource.get_button_text_props())],
     ))
-    .observe(|_: On<Pointer<Click>>, mut ew: MessageWriter<AppExit>| {
+    .observe(|_: On<MenuActivate>, mut ew: MessageWriter<AppExit>| {
         ew.write(AppExit::Success);
     });
 }
@@ -86,33 +100,64 @@ fn actions(cmd: &mut RelatedSpawnerCommands<'_, ChildOf>, text_resource: &Res<Te
 fn content(
     cmd: &mut RelatedSpawnerCommands<'_, ChildOf>,
     text_resource: &Res<TextResource>,
-    run_duration: &Res<RunDuration>,
+    summary: &Summary,
 ) {
-    let secs = run_duration
-        .results
-        .iter()
-        .map(|x| x.as_secs_f32())
-        .sum::<f32>();
+    let Summary {
+        run_duration,
+        run_records,
+        real_time,
+        attempts,
+        integrity,
+        save_data,
+    } = summary;
+    let time_format = save_data.time_format;
+    let time = run_duration.results.iter().sum::<Duration>();
+
+    let run_record = match integrity.is_run_valid() {
+        true => format_record(run_records.run.as_ref(), time_format),
+        false => format_violations(integrity.run_violations()),
+    };
 
     cmd.spawn((
         NodeBuilder::new().get_card(),
-        children![(
-            NodeBuilder::new().with_margin(UiRect::all(MARGIN)).get(),
-            children![(
-                Text(format!("Run: {}", format_duration(secs))),
-                text_resource.get_text_props(32.0, Resurrect64::BRIGHT_GREEN),
-            )],
-        )],
+        children![
+            (
+                NodeBuilder::new().with_margin(UiRect::all(MARGIN)).get(),
+                children![(
+                    Text(format!("Run: {}{}", time_format.format(time), run_record)),
+                    text_resource.get_text_props(32.0, Resurrect64::BRIGHT_GREEN),
+                )],
+            ),
+            (
+                NodeBuilder::new().get(),
+                children![(
+                    Text(format!(
+                        "Real Time: {} | Attempts: {}",
+                        time_format.format(real_time.0.elapsed()),
+                        attempts.0.iter().sum::<u32>()
+                    )),
+                    text_resource.get_text_props(24.0, Resurrect64::BRIGHT_GREEN),
+                )],
+            ),
+        ],
     ))
     .with_children(|cmd| {
         run_duration.results.iter().enumerate().for_each(|(i, x)| {
+            let level = NonZeroUsize::MIN.saturating_add(i);
+            let level_record = match integrity.is_level_valid(level) {
+                true => format_record(run_records.levels.get(&level.get()), time_format),
+                false => format_violations(integrity.level_violations(level)),
+            };
+
             cmd.spawn((
                 NodeBuilder::new().get(),
                 children![(
                     Text(format!(
-                        "Level {}: {}",
-                        i + 1,
-                        format_duration(x.as_secs_f32())
+                        "Level {}: {} ({} attempts){}",
+                        level,
+                        time_format.format(*x),
+                        attempts.get(level),
+                        level_record
                     )),
                     text_resource.get_text_props(
                         24.0,
@@ -124,10 +169,71 @@ fn content(
                     ),
                 )],
             ));
+
+            let Some(splits) = run_records
+                .splits
+                .get(&level.get())
+                .filter(|x| !x.is_empty())
+            else {
+                return;
+            };
+
+            cmd.spawn((
+                NodeBuilder::new().get(),
+                children![(
+                    Text(format!(
+                        "Splits: {}",
+                        splits
+                            .iter()
+                            .map(|x| format_split(x, time_format))
+                            .collect::<Vec<_>>()
+                            .join(" ")
+                    )),
+                    text_resource.get_text_props(16.0, Resurrect64::LIGHT_PURPLE),
+                )],
+            ));
         });
     });
 }
 
-fn handle_restart(_: On<Pointer<Click>>, mut ns_app_state: ResMut<NextState<AppState>>) {
+fn format_split(split: &RecordResult, time_format: TimeFormat) -> String {
+    match split.pb {
+        Some(pb) => format!(
+            "{} ({})",
+            time_format.format(split.time),
+            time_format.format_delta(split.time, pb)
+        ),
+        None => time_format.format(split.time),
+    }
+}
+
+fn format_violations(violations: impl Iterator<Item = Violation>) -> String {
+    format!(
+        " | Invalid ({})",
+        violations
+            .map(|x| x.to_string())
+            .collect::<Vec<_>>()
+            .join(", ")
+    )
+}
+
+fn format_record(record: Option<&RecordResult>, time_format: TimeFormat) -> String {
+    let Some(record) = record else {
+        return String::new();
+    };
+
+    let Some(pb) = record.pb else {
+        return String::from(" | New PB!");
+    };
+
+    format!(
+        " | PB {} ({}){}",
+        time_format.format(pb),
+        time_format.format_delta(record.time, pb),
+        if record.is_pb() { " New PB!" } else { "" }
+    )
+}
+
+fn handle_restart(_: On<MenuActivate>, mut ns_app_state: ResMut<NextState<AppState>>) {
     ns_app_state.set(AppState::InGame);
 }
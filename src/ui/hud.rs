@@ -1,7 +1,9 @@
+use std::time::Duration;
+
 use avian3d::prelude::*;
 use bevy::prelude::*;
 
-use crate::core::*;
+use crate::{core::*, save::SaveData};
 
 use super::*;
 
@@ -20,6 +22,9 @@ impl Plugin for HudPlugin {
                     update_speed_ui,
                     update_level_duration_ui,
                     update_run_duration_ui,
+                    update_real_time_ui,
+                    show_split_delta,
+                    cleanup_timed::<SplitDeltaCard>,
                 )
                     .in_set(GameplaySet),
             );
@@ -29,7 +34,7 @@ impl Plugin for HudPlugin {
 fn setup_hud(mut cmd: Commands, text_resource: Res<TextResource>) {
     cmd.spawn((
         layout(),
-        children![header(&text_resource), content(&text_resource),],
+        children![header(&text_resource), splits(), content(&text_resource),],
     ));
 }
 
@@ -74,10 +79,27 @@ fn header(text_resource: &Res<TextResource>) -> impl Bundle {
                     text_resource.get_hud_text_props(19.),
                 )]
             ),
+            (
+                NodeBuilder::new().get_card(),
+                Pickable::IGNORE,
+                children![(
+                    Text(String::from("")),
+                    RealTimeText,
+                    text_resource.get_hud_text_props(19.),
+                )]
+            ),
         ],
     )
 }
 
+fn splits() -> impl Bundle {
+    (
+        NodeBuilder::new().get(),
+        Pickable::IGNORE,
+        SplitDeltaContainer,
+    )
+}
+
 fn content(text_resource: &Res<TextResource>) -> impl Bundle {
     (
         NodeBuilder::new()
@@ -113,16 +135,18 @@ fn update_speed_ui(
 }
 
 fn update_level_duration_ui(
-    duration: ResMut<LevelDuration>,
+    duration: Res<LevelDuration>,
     mut text_query: Query<&mut Text, With<LevelDurationText>>,
-    time: Res<Time>,
     current_lvl: Res<CurrentLevel>,
+    attempts: Res<Attempts>,
+    save_data: Res<SaveData>,
 ) {
-    let stopwatch = duration.into_inner();
-    stopwatch.0.tick(time.delta());
-    let secs = stopwatch.0.elapsed_secs();
-
-    let new_text = format!("Level {}: {}", current_lvl.get(), format_duration(secs));
+    let new_text = format!(
+        "Level {}: {} | Attempt {}",
+        current_lvl.get(),
+        save_data.time_format.format(duration.0.elapsed()),
+        attempts.get(current_lvl.get())
+    );
 
     for mut text in &mut text_query {
         text.0 = new_text.clone();
@@ -133,16 +157,78 @@ fn update_run_duration_ui(
     run_duration: Res<RunDuration>,
     level_duration: Res<LevelDuration>,
     mut text_query: Query<&mut Text, With<RunDurationText>>,
+    save_data: Res<SaveData>,
 ) {
     let stopwatch = level_duration.into_inner();
-    let secs = run_duration
-        .results
-        .iter()
-        .map(|x| x.as_secs_f32())
-        .sum::<f32>()
-        + stopwatch.0.elapsed_secs();
-
-    let new_text = format!("Run: {}", format_duration(secs));
+    let time = run_duration.results.iter().sum::<Duration>() + stopwatch.0.elapsed();
+
+    let new_text = format!("Run: {}", save_data.time_format.format(time));
+
+    for mut text in &mut text_query {
+        text.0 = new_text.clone();
+    }
+}
+
+fn show_split_delta(
+    mut cmd: Commands,
+    mut er: MessageReader<CheckpointReached>,
+    container: Single<Entity, With<SplitDeltaContainer>>,
+    q_card: Query<Entity, With<SplitDeltaCard>>,
+    save_data: Res<SaveData>,
+    current_lvl: Res<CurrentLevel>,
+    text_resource: Res<TextResource>,
+) {
+    let container = container.into_inner();
+
+    for CheckpointReached(split) in er.read() {
+        for card in &q_card {
+            cmd.entity(card).despawn();
+        }
+
+        let pb = save_data
+            .level_pb(current_lvl.get().get())
+            .and_then(|x| x.split(split.index));
+
+        let time_format = save_data.time_format;
+        let (delta, color) = match pb {
+            Some(pb) => (
+                format!(" ({})", time_format.format_delta(split.time, pb)),
+                match split.time < pb {
+                    true => Resurrect64::BRIGHT_GREEN,
+                    false => Resurrect64::SCARLET,
+                },
+            ),
+            None => (String::new(), HUD_TEXT_COLOR),
+        };
+
+        cmd.entity(container).with_child((
+            NodeBuilder::new().get_card(),
+            Pickable::IGNORE,
+            SplitDeltaCard,
+            Lifetime {
+                timer: Timer::from_seconds(2., TimerMode::Once),
+            },
+            children![(
+                Text(format!(
+                    "Split: {}{}",
+                    time_format.format(split.time),
+                    delta
+                )),
+                text_resource.get_text_props(24.0, color),
+            )],
+        ));
+    }
+}
+
+fn update_real_time_ui(
+    real_time: Res<RealTimeDuration>,
+    mut text_query: Query<&mut Text, With<RealTimeText>>,
+    save_data: Res<SaveData>,
+) {
+    let new_text = format!(
+        "Real Time: {}",
+        save_data.time_format.format(real_time.0.elapsed())
+    );
 
     for mut text in &mut text_query {
         text.0 = new_text.clone();
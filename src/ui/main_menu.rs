@@ -11,10 +11,18 @@ pub struct MainMenuPlugin;
 
 impl Plugin for MainMenuPlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(OnEnter(AppState::MainMenu), setup)
+        app.add_systems(OnEnter(AppState::MainMenu), (setup_camera, setup))
             .add_systems(
                 OnExit(AppState::MainMenu),
                 (cleanup::<MainMenu>, cleanup::<Camera3d>),
+            )
+            .add_systems(
+                OnEnter(SettingsMenuState::Open),
+                cleanup::<MainMenu>.run_if(in_state(AppState::MainMenu)),
+            )
+            .add_systems(
+                OnExit(SettingsMenuState::Open),
+                setup.run_if(in_state(AppState::MainMenu)),
             );
     }
 }
@@ -33,12 +41,14 @@ fn header(cmd: &mut RelatedSpawnerCommands<'_, ChildOf>, text_resource: &Res<Tex
     cmd.spawn(get_header(text_resource));
 }
 
-fn layout<'a>(cmd: &'a mut Commands) -> EntityCommands<'a> {
+fn setup_camera(mut cmd: Commands) {
     cmd.spawn((
         Camera3d::default(),
         Transform::from_translation(Vec3::ZERO.with_y(15.)),
     ));
+}
 
+fn layout<'a>(cmd: &'a mut Commands) -> EntityCommands<'a> {
     cmd.spawn((
         BackgroundColor(BACKGROUND),
         NodeBuilder::new().with_grow(true).get(),
@@ -53,16 +63,22 @@ fn content(cmd: &mut RelatedSpawnerCommands<'_, ChildOf>, text_resource: &Res<Te
     ))
     .observe(handle_play);
 
+    cmd.spawn((
+        NodeBuilder::new().get_button(),
+        children![(Text::new("Settings"), text_resource.get_button_text_props())],
+    ))
+    .observe(open_settings);
+
     #[cfg(not(target_arch = "wasm32"))]
     cmd.spawn((
         NodeBuilder::new().get_button(),
         children![(Text::new("Quit"), text_resource.get_button_text_props(),)],
     ))
-    .observe(|_: On<Pointer<Click>>, mut ew: MessageWriter<AppExit>| {
+    .observe(|_: On<MenuActivate>, mut ew: MessageWriter<AppExit>| {
         ew.write(AppExit::Success);
     });
 }
 
-fn handle_play(_: On<Pointer<Click>>, mut ns: ResMut<NextState<AppState>>) {
+fn handle_play(_: On<MenuActivate>, mut ns: ResMut<NextState<AppState>>) {
     ns.set(AppState::InGame);
 }
@@ -1,10 +1,12 @@
 mod core;
+mod focus;
 mod game_over;
 mod hud;
 mod loading;
 mod main_menu;
 mod node_builder;
 mod pause;
+mod settings;
 mod text_resource;
 
 pub use core::*;
@@ -14,12 +16,14 @@ use bevy_dev_tools::fps_overlay::*;
 use bevy_egui::EguiPlugin;
 use bevy_fps_controller::controller::*;
 use bevy_inspector_egui::quick::WorldInspectorPlugin;
+use focus::{FocusPlugin, MenuActivate};
 use game_over::GameOverPlugin;
 use hud::HudPlugin;
 use loading::LoadingScreenPlugin;
 use main_menu::MainMenuPlugin;
 use node_builder::*;
 use pause::PausePlugin;
+use settings::*;
 
 use crate::prelude::*;
 
@@ -50,6 +54,8 @@ impl Plugin for UiPlugin {
                 LoadingScreenPlugin,
                 PausePlugin,
                 HudPlugin,
+                FocusPlugin,
+                SettingsMenuPlugin,
             ))
             .add_systems(Startup, setup_font)
             .add_systems(Update, button_system);
@@ -9,7 +9,15 @@ pub struct PausePlugin;
 impl Plugin for PausePlugin {
     fn build(&self, app: &mut App) {
         app.add_systems(OnEnter(PausedState::Paused), setup_pause_menu)
-            .add_systems(OnExit(PausedState::Paused), cleanup::<PauseMenu>);
+            .add_systems(OnExit(PausedState::Paused), cleanup::<PauseMenu>)
+            .add_systems(
+                OnEnter(SettingsMenuState::Open),
+                cleanup::<PauseMenu>.run_if(in_state(PausedState::Paused)),
+            )
+            .add_systems(
+                OnExit(SettingsMenuState::Open),
+                setup_pause_menu.run_if(in_state(PausedState::Paused)),
+            );
     }
 }
 
@@ -62,6 +70,12 @@ fn pause_menu_content(
     ))
     .observe(handle_resume);
 
+    cmd.spawn((
+        NodeBuilder::new().get_button(),
+        children![(Text::new("Settings"), text_resource.get_button_text_props())],
+    ))
+    .observe(open_settings);
+
     cmd.spawn((
         NodeBuilder::new().get_button(),
         children![(
@@ -70,7 +84,7 @@ fn pause_menu_content(
         )],
     ))
     .observe(
-        |_: On<Pointer<Click>>, mut ns_app_state: ResMut<NextState<AppState>>| {
+        |_: On<MenuActivate>, mut ns_app_state: ResMut<NextState<AppState>>| {
             ns_app_state.set(AppState::MainMenu);
         },
     );
@@ -80,11 +94,11 @@ fn pause_menu_content(
         NodeBuilder::new().get_button(),
         children![(Text::new("Quit"), text_resource.get_button_text_props())],
     ))
-    .observe(|_: On<Pointer<Click>>, mut ew: MessageWriter<AppExit>| {
+    .observe(|_: On<MenuActivate>, mut ew: MessageWriter<AppExit>| {
         ew.write(AppExit::Success);
     });
 }
 
-fn handle_resume(_: On<Pointer<Click>>, mut ns: ResMut<NextState<PausedState>>) {
+fn handle_resume(_: On<MenuActivate>, mut ns: ResMut<NextState<PausedState>>) {
     ns.set(PausedState::Running);
 }
@@ -0,0 +1,245 @@
+use bevy::{ecs::relationship::RelatedSpawnerCommands, prelude::*, window::PresentMode};
+
+use crate::{audio::AudioBus, core::*, settings::Settings};
+
+use super::*;
+
+pub struct SettingsMenuPlugin;
+
+impl Plugin for SettingsMenuPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_state::<SettingsMenuState>()
+            .add_systems(OnEnter(SettingsMenuState::Open), setup)
+            .add_systems(OnExit(SettingsMenuState::Open), cleanup::<SettingsMenu>)
+            .add_systems(OnExit(AppState::MainMenu), close)
+            .add_systems(OnExit(PausedState::Paused), close)
+            .add_systems(
+                Update,
+                update_values
+                    .run_if(resource_changed::<Settings>)
+                    .run_if(in_state(SettingsMenuState::Open)),
+            );
+    }
+}
+
+/// Shown in place of the main or pause menu.
+#[derive(States, Default, Debug, Clone, PartialEq, Eq, Hash)]
+pub enum SettingsMenuState {
+    #[default]
+    Closed,
+    Open,
+}
+
+#[derive(Component)]
+struct SettingsMenu;
+
+/// Row of the settings menu, also marks the text showing its value.
+#[derive(Component, Debug, Clone, Copy)]
+enum Setting {
+    MouseSensitivity,
+    Fov,
+    Volume(AudioBus),
+    PausedMusic,
+    Bloom,
+    Fog,
+    Shadows,
+    PresentMode,
+    FpsOverlay,
+}
+
+impl Setting {
+    /// Controls and video on the left, audio on the right.
+    const COLUMNS: [&[Self]; 2] = [
+        &[
+            Self::MouseSensitivity,
+            Self::Fov,
+            Self::Bloom,
+            Self::Fog,
+            Self::Shadows,
+            Self::PresentMode,
+            Self::FpsOverlay,
+        ],
+        &[
+            Self::Volume(AudioBus::Master),
+            Self::Volume(AudioBus::Music),
+            Self::Volume(AudioBus::Sfx),
+            Self::Volume(AudioBus::Ambient),
+            Self::Volume(AudioBus::Ui),
+            Self::PausedMusic,
+        ],
+    ];
+
+    fn label(&self) -> String {
+        match self {
+            Self::MouseSensitivity => String::from("Mouse Sensitivity"),
+            Self::Fov => String::from("Field of View"),
+            Self::Volume(bus) => format!("{} Volume", bus.name()),
+            Self::PausedMusic => String::from("Paused Music"),
+            Self::Bloom => String::from("Bloom"),
+            Self::Fog => String::from("Fog"),
+            Self::Shadows => String::from("Shadows"),
+            Self::PresentMode => String::from("VSync"),
+            Self::FpsOverlay => String::from("FPS Counter"),
+        }
+    }
+
+    fn value(&self, settings: &Settings) -> String {
+        let toggle = |x: bool| match x {
+            true => String::from("On"),
+            false => String::from("Off"),
+        };
+
+        match self {
+            Self::MouseSensitivity => format!("{:.2}", settings.mouse_sensitivity),
+            Self::Fov => format!("{:.0}°", settings.fov),
+            Self::Volume(bus) => format!("{:.0}%", settings.mix.get(*bus) * 100.),
+            Self::PausedMusic => match settings.paused_music_volume > 0. {
+                true => format!("{:.0}%", settings.paused_music_volume * 100.),
+                false => String::from("Stopped"),
+            },
+            Self::Bloom => toggle(settings.bloom),
+            Self::Fog => toggle(settings.fog),
+            Self::Shadows => toggle(settings.shadows),
+            Self::PresentMode => toggle(settings.present_mode != PresentMode::AutoNoVsync),
+            Self::FpsOverlay => toggle(settings.fps_overlay),
+        }
+    }
+
+    /// Steps the setting up or down, toggles are flipped either way.
+    fn adjust(&self, settings: &mut Settings, up: bool) {
+        let step = match up {
+            true => 1.,
+            false => -1.,
+        };
+
+        match self {
+            Self::MouseSensitivity => {
+                settings.mouse_sensitivity = ((settings.mouse_sensitivity + step * 0.05) * 20.)
+                    .round()
+                    .clamp(1., 100.)
+                    / 20.;
+            }
+            Self::Fov => settings.fov = (settings.fov + step * 5.).clamp(50., 120.),
+            Self::Volume(bus) => {
+                let volume = settings.mix.get_mut(*bus);
+                *volume = ((*volume + step * 0.1) * 10.).round().clamp(0., 10.) / 10.;
+            }
+            Self::PausedMusic => {
+                settings.paused_music_volume = ((settings.paused_music_volume + step * 0.1) * 10.)
+                    .round()
+                    .clamp(0., 10.)
+                    / 10.;
+            }
+            Self::Bloom => settings.bloom = !settings.bloom,
+            Self::Fog => settings.fog = !settings.fog,
+            Self::Shadows => settings.shadows = !settings.shadows,
+            Self::PresentMode => {
+                settings.present_mode = match settings.present_mode {
+                    PresentMode::AutoNoVsync => PresentMode::AutoVsync,
+                    _ => PresentMode::AutoNoVsync,
+                };
+            }
+            Self::FpsOverlay => settings.fps_overlay = !settings.fps_overlay,
+        }
+    }
+}
+
+pub(super) fn open_settings(_: On<MenuActivate>, mut ns: ResMut<NextState<SettingsMenuState>>) {
+    ns.set(SettingsMenuState::Open);
+}
+
+fn close(mut ns: ResMut<NextState<SettingsMenuState>>) {
+    ns.set(SettingsMenuState::Closed);
+}
+
+fn setup(mut cmd: Commands, text_resource: Res<TextResource>, settings: Res<Settings>) {
+    cmd.spawn((
+        NodeBuilder::new().with_grow(true).get(),
+        SettingsMenu,
+        BackgroundColor(BACKGROUND.with_alpha(0.5)),
+    ))
+    .with_children(|cmd| {
+        cmd.spawn(NodeBuilder::new().get_card())
+            .with_children(|cmd| {
+                cmd.spawn((Text::new("Settings"), text_resource.get_hud_text_props(48.)));
+
+                cmd.spawn(
+                    NodeBuilder::new()
+                        .with_direction(FlexDirection::Row)
+                        .with_align_items(AlignItems::Start)
+                        .get(),
+                )
+                .with_children(|cmd| {
+                    for column in Setting::COLUMNS {
+                        cmd.spawn(NodeBuilder::new().get()).with_children(|cmd| {
+                            for setting in column {
+                                row(cmd, &text_resource, &settings, *setting);
+                            }
+                        });
+                    }
+                });
+
+                cmd.spawn((
+                    NodeBuilder::new().get_button(),
+                    children![(Text::new("Back"), text_resource.get_button_text_props())],
+                ))
+                .observe(close_settings);
+            });
+    });
+}
+
+fn row(
+    cmd: &mut RelatedSpawnerCommands<'_, ChildOf>,
+    text_resource: &Res<TextResource>,
+    settings: &Settings,
+    setting: Setting,
+) {
+    cmd.spawn(NodeBuilder::new().with_direction(FlexDirection::Row).get())
+        .with_children(|cmd| {
+            cmd.spawn((
+                Text::new(setting.label()),
+                text_resource.get_button_text_props(),
+                Node {
+                    width: Val::Px(260.),
+                    ..default()
+                },
+            ));
+
+            cmd.spawn((
+                NodeBuilder::new().get_button(),
+                children![(Text::new("<"), text_resource.get_button_text_props())],
+            ))
+            .observe(move |_: On<MenuActivate>, mut settings: ResMut<Settings>| {
+                setting.adjust(&mut settings, false);
+            });
+
+            cmd.spawn((
+                Text::new(setting.value(settings)),
+                text_resource.get_button_text_props(),
+                TextLayout::new_with_justify(Justify::Center),
+                Node {
+                    width: Val::Px(120.),
+                    ..default()
+                },
+                setting,
+            ));
+
+            cmd.spawn((
+                NodeBuilder::new().get_button(),
+                children![(Text::new(">"), text_resource.get_button_text_props())],
+            ))
+            .observe(move |_: On<MenuActivate>, mut settings: ResMut<Settings>| {
+                setting.adjust(&mut settings, true);
+            });
+        });
+}
+
+fn close_settings(_: On<MenuActivate>, mut ns: ResMut<NextState<SettingsMenuState>>) {
+    ns.set(SettingsMenuState::Closed);
+}
+
+fn update_values(settings: Res<Settings>, mut q_value: Query<(&Setting, &mut Text)>) {
+    for (setting, mut text) in &mut q_value {
+        text.0 = setting.value(&settings);
+    }
+}
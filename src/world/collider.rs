@@ -1,14 +1,14 @@
-use bevy::{
-    audio::{PlaybackMode, Volume},
-    prelude::*,
-};
+use bevy::prelude::*;
 
 use avian3d::prelude::*;
 use bevy_hanabi::ParticleEffect;
-use std::num::NonZeroUsize;
+use std::{num::NonZeroUsize, time::Duration};
 
 use super::*;
 
+const PROP_IMPACT_MIN_SPEED: f32 = 2.;
+const PROP_IMPACT_MAX_SPEED: f32 = 15.;
+
 pub struct ColliderPlugin;
 
 impl Plugin for ColliderPlugin {
@@ -21,6 +21,7 @@ impl Plugin for ColliderPlugin {
                 boost_colliders,
                 end_colliders,
                 checkpoint_colliders,
+                kill_zone_colliders,
             )
                 .after(spawn_world)
                 .run_if(in_state(AppState::InGame)),
@@ -38,14 +39,48 @@ fn prop_colliders(
     }
 
     for prop in &q_props {
-        cmd.entity(prop).insert((
-            Ready,
-            CollisionLayers::new(CollisionLayer::Prop, LayerMask::ALL),
-            ColliderConstructor::ConvexHullFromMesh,
-            TransformInterpolation,
-            RigidBody::Dynamic,
-        ));
+        cmd.entity(prop)
+            .insert((
+                Ready,
+                CollisionLayers::new(CollisionLayer::Prop, LayerMask::ALL),
+                ColliderConstructor::ConvexHullFromMesh,
+                TransformInterpolation,
+                RigidBody::Dynamic,
+                CollisionEventsEnabled,
+            ))
+            .observe(prop_collision);
+    }
+}
+
+fn prop_collision(
+    trigger: On<CollisionStart>,
+    mut cmd: Commands,
+    bank: Res<SoundBank>,
+    q_velocity: Query<&LinearVelocity>,
+    q_sensor: Query<(), With<Sensor>>,
+    q_prop: Query<(), With<Prop>>,
+) {
+    let prop = trigger.collider1;
+    let other_entity = trigger.collider2;
+
+    // Both props observe their collision, only one of them plays it.
+    if q_sensor.contains(other_entity) || (q_prop.contains(other_entity) && other_entity < prop) {
+        return;
+    }
+
+    let velocity = |x| q_velocity.get(x).map_or(Vec3::ZERO, |velocity| velocity.0);
+    let speed = (velocity(prop) - velocity(other_entity)).length();
+
+    // Resting and sliding contacts would keep clicking.
+    if speed < PROP_IMPACT_MIN_SPEED {
+        return;
     }
+
+    cmd.entity(prop).with_child((
+        AudioPlayer::new(bank.get("land")),
+        spatial_playback(),
+        BusSound::new(AudioBus::Sfx, (speed / PROP_IMPACT_MAX_SPEED).min(1.) * 0.3),
+    ));
 }
 
 fn ground_colliders(
@@ -94,12 +129,40 @@ fn checkpoint_collision(
     mut history: ResMut<History>,
     current_lvl: Res<CurrentLevel>,
     fx: Res<ParticleEffects>,
-    sounds: Res<Sounds>,
+    bank: Res<SoundBank>,
+    q_velocity: Query<&LinearVelocity>,
+    q_checkpoint: Query<&CheckPoint>,
+    q_gtf: Query<&GlobalTransform>,
+    level_duration: Res<LevelDuration>,
+    mut ew: MessageWriter<CheckpointReached>,
 ) {
-    history.0.push(trigger.collider1);
-
+    let checkpoint = trigger.collider1;
     let other_entity = trigger.collider2;
 
+    let Ok(index) = q_checkpoint.get(checkpoint).map(|x| x.index) else {
+        return;
+    };
+
+    if !history.accepts(checkpoint, index, q_checkpoint.iter().map(|x| x.index)) {
+        return;
+    }
+
+    let split = level_duration.0.elapsed();
+    let is_alternative = history.checkpoints.last().is_some_and(|x| x.index == index);
+
+    history.checkpoints.push(HistoryEntry {
+        checkpoint,
+        index,
+        entry_velocity: q_velocity
+            .get(other_entity)
+            .map_or(Vec3::ZERO, |velocity| velocity.0),
+        split,
+    });
+
+    if !is_alternative {
+        ew.write(CheckpointReached(Split { index, time: split }));
+    }
+
     cmd.entity(other_entity).with_child((
         ParticleEffect::new(fx.get_checkpoint_fx(current_lvl.get())),
         Visibility::Visible,
@@ -108,14 +171,61 @@ fn checkpoint_collision(
         },
     ));
 
-    cmd.spawn((
-        AudioPlayer::new(sounds.glass_sound.clone()),
-        PlaybackSettings {
-            mode: PlaybackMode::Despawn,
-            volume: Volume::Linear(0.1),
-            ..default()
-        },
-    ));
+    if let Ok(checkpoint_gtf) = q_gtf.get(checkpoint) {
+        cmd.spawn((
+            AudioPlayer::new(bank.get("glass")),
+            spatial_playback(),
+            BusSound::new(AudioBus::Sfx, 0.1),
+            Transform::from_translation(checkpoint_gtf.translation()),
+        ));
+    }
+}
+
+fn kill_zone_colliders(
+    mut cmd: Commands,
+    main_scene: Res<MainScene>,
+    q_kill_zone: Query<Entity, (With<KillZone>, Without<Ready>)>,
+) {
+    if !main_scene.is_spawned {
+        return;
+    }
+
+    for kill_zone in &q_kill_zone {
+        cmd.entity(kill_zone)
+            .insert((
+                Ready,
+                CollisionLayers::new(
+                    CollisionLayer::KillZone,
+                    [CollisionLayer::Player, CollisionLayer::Prop],
+                ),
+                ColliderConstructor::ConvexHullFromMesh,
+                Sensor,
+                CollisionEventsEnabled,
+                Visibility::Hidden,
+            ))
+            .observe(kill_zone_collision);
+    }
+}
+
+fn kill_zone_collision(
+    trigger: On<CollisionStart>,
+    history: Res<History>,
+    q_checkpoint: Query<(&GlobalTransform, &CheckPoint)>,
+    q_player: Query<(), With<LogicalPlayer>>,
+    q_prop: Query<(), With<Prop>>,
+    mut ew_player: MessageWriter<Respawn<LogicalPlayer>>,
+    mut ew_prop: MessageWriter<Respawn<Prop>>,
+) {
+    let other_entity = trigger.collider2;
+    let spawn_point = history.last(q_checkpoint);
+
+    if q_player.contains(other_entity) {
+        ew_player.write(Respawn::new(spawn_point, RespawnCause::KillZone));
+    }
+
+    if q_prop.contains(other_entity) {
+        ew_prop.write(Respawn::new(spawn_point, RespawnCause::KillZone));
+    }
 }
 
 fn end_colliders(
@@ -140,18 +250,40 @@ fn end_colliders(
 }
 
 fn end_collision(
-    _: On<CollisionStart>,
+    trigger: On<CollisionStart>,
     current_lvl: Res<CurrentLevel>,
     mut ns: ResMut<NextState<AppState>>,
     mut ew: MessageWriter<SpawnLevel>,
+    mut ew_finished: MessageWriter<LevelFinished>,
     level_duration: Res<LevelDuration>,
     mut run_duration: ResMut<RunDuration>,
+    mut real_time: ResMut<RealTimeDuration>,
+    levels: Res<Levels>,
+    history: Res<History>,
+    q_checkpoint: Query<&CheckPoint>,
+    spatial_query: SpatialQuery,
+    q_player: Query<(&Collider, &Position, &Rotation, &LinearVelocity), With<LogicalPlayer>>,
 ) {
-    let next_level = current_lvl.get().get() + 1;
+    if let Some(index) = history.next_index(q_checkpoint.iter().map(|x| x.index)) {
+        info!("Checkpoint {index} is missing, the level can't be finished yet.");
+        return;
+    }
 
-    run_duration.results[current_lvl.get().get() - 1] = level_duration.0.elapsed();
+    let next_level = current_lvl.get().get() + 1;
 
-    if next_level > LEVEL_COUNT {
+    run_duration.results[current_lvl.get().get() - 1] = end_time(
+        level_duration.0.elapsed(),
+        trigger.collider1,
+        q_player.get(trigger.collider2).ok(),
+        &spatial_query,
+    );
+    ew_finished.write(LevelFinished {
+        level: current_lvl.get(),
+        splits: history.splits(),
+    });
+
+    if next_level > levels.count() {
+        real_time.0.pause();
         ns.set(AppState::GameOver);
         return;
     }
@@ -159,6 +291,43 @@ fn end_collision(
     ew.write(SpawnLevel(NonZeroUsize::new(next_level).unwrap()));
 }
 
+/// Level time the player touched `end` at. `elapsed` counts the whole tick of the touch, so the
+/// player is cast along its velocity from where it started the tick, and the part of the tick left
+/// after the hit is taken back.
+fn end_time(
+    elapsed: Duration,
+    end: Entity,
+    player: Option<(&Collider, &Position, &Rotation, &LinearVelocity)>,
+    spatial_query: &SpatialQuery,
+) -> Duration {
+    let Some((collider, position, rotation, velocity)) = player else {
+        return elapsed;
+    };
+
+    let travel = velocity.0 / TICK_HZ as f32;
+    let Ok(direction) = Dir3::new(travel) else {
+        return elapsed;
+    };
+
+    let hit = spatial_query.cast_shape(
+        collider,
+        position.0 - travel,
+        rotation.0,
+        direction,
+        &ShapeCastConfig::from_max_distance(travel.length()),
+        &SpatialQueryFilter::from_mask(CollisionLayer::End),
+    );
+
+    let Some(hit) = hit.filter(|x| x.entity == end) else {
+        return elapsed;
+    };
+
+    let tick = Duration::from_secs(1) / TICK_HZ as u32;
+    let remaining = 1. - (hit.distance / travel.length()).clamp(0., 1.);
+
+    elapsed.saturating_sub(tick.mul_f32(remaining))
+}
+
 fn boost_colliders(
     mut cmd: Commands,
     main_scene: Res<MainScene>,
@@ -204,7 +373,7 @@ fn boost_collision(
     q_gtf: Query<&GlobalTransform>,
     fx: Res<ParticleEffects>,
     mut q_boosted: Query<&mut LinearVelocity>,
-    sounds: Res<Sounds>,
+    bank: Res<SoundBank>,
 ) {
     let boost = trigger.collider1;
 
@@ -240,11 +409,9 @@ fn boost_collision(
     ));
 
     cmd.spawn((
-        AudioPlayer::new(sounds.boost_sound.clone()),
-        PlaybackSettings {
-            mode: PlaybackMode::Despawn,
-            volume: Volume::Linear(0.2),
-            ..default()
-        },
+        AudioPlayer::new(bank.get("boost")),
+        spatial_playback(),
+        BusSound::new(AudioBus::Sfx, 0.2),
+        Transform::from_translation(gtf.translation()),
     ));
 }
@@ -2,17 +2,15 @@ use std::num::NonZeroUsize;
 
 use bevy::{gltf::Gltf, prelude::*};
 
-use crate::core::*;
-
 #[derive(Component, Reflect, Debug)]
 #[reflect(Component)]
 pub struct Sun;
 
 #[derive(Resource)]
 pub struct MainScene {
-    pub(super) levels: [Handle<Gltf>; LEVEL_COUNT],
+    pub(super) levels: Vec<Handle<Gltf>>,
     pub(super) is_spawned: bool,
-    pub(super) skyboxes: [Handle<Image>; LEVEL_COUNT],
+    pub(super) skyboxes: Vec<Handle<Image>>,
 }
 
 impl MainScene {
@@ -26,7 +26,7 @@ impl Plugin for WorldPlugin {
             })
             .add_plugins((WaterPlugin, ColliderPlugin));
         app.add_message::<SpawnLevel>()
-            .add_systems(Startup, setup)
+            .add_systems(Update, setup.run_if(resource_added::<Levels>))
             .add_systems(
                 FixedUpdate,
                 (spawn_level, spawn_world)
@@ -35,7 +35,7 @@ impl Plugin for WorldPlugin {
             )
             .add_systems(
                 FixedUpdate,
-                (setup_water, translate_water)
+                (setup_water, translate_water, spawn_points)
                     .after(spawn_world)
                     .run_if(in_state(AppState::InGame)),
             )
@@ -67,7 +67,7 @@ fn setup(
     mut commands: Commands,
     mut window: Query<&mut Window>,
     assets: Res<AssetServer>,
-
+    levels: Res<Levels>,
     mut loading: ResMut<AssetsLoading>,
 ) {
     let mut window = window.single_mut().unwrap();
@@ -82,17 +82,15 @@ fn setup(
         Transform::from_xyz(4.0, 7.0, -4.0).looking_at(Vec3::ZERO, Vec3::Y),
     ));
 
-    let levels: [Handle<Gltf>; LEVEL_COUNT] = (1..=LEVEL_COUNT)
-        .map(|x| assets.load(format!("level{:?}.glb", x)) as Handle<Gltf>)
-        .collect::<Vec<Handle<Gltf>>>()
-        .try_into()
-        .unwrap();
+    let skyboxes: Vec<Handle<Image>> = levels
+        .iter()
+        .map(|x| assets.load(x.skybox.clone()))
+        .collect();
 
-    let skyboxes: [Handle<Image>; LEVEL_COUNT] = (1..=LEVEL_COUNT)
-        .map(|x| assets.load(format!("skybox/skybox_{:?}_skybox.ktx2", x)) as Handle<Image>)
-        .collect::<Vec<Handle<Image>>>()
-        .try_into()
-        .unwrap();
+    let levels: Vec<Handle<Gltf>> = levels
+        .iter()
+        .map(|x| assets.load(x.scene.clone()))
+        .collect();
 
     levels
         .iter()
@@ -113,9 +111,14 @@ fn setup(
     commands.insert_resource(CurrentLevel(NonZeroUsize::MIN));
 }
 
-fn reset_world(mut world: ResMut<MainScene>, mut current_level: ResMut<CurrentLevel>) {
+fn reset_world(
+    mut world: ResMut<MainScene>,
+    mut current_level: ResMut<CurrentLevel>,
+    mut history: ResMut<History>,
+) {
     world.is_spawned = false;
     current_level.0 = NonZeroUsize::MIN;
+    history.reset();
 }
 
 fn setup_water(mut q_water: Query<&mut Transform, (With<WaterTiles>, Without<Ready>)>) {
@@ -127,11 +130,11 @@ fn setup_water(mut q_water: Query<&mut Transform, (With<WaterTiles>, Without<Rea
 fn translate_water(
     mut q_water: Query<&mut Transform, With<WaterTiles>>,
     history: Res<History>,
-    q_gtf: Query<&GlobalTransform, With<CheckPoint>>,
+    q_checkpoint: Query<(&GlobalTransform, &CheckPoint)>,
 ) {
-    let spawn_point = history.last(q_gtf);
+    let spawn_point = history.last(q_checkpoint);
     for mut water in &mut q_water {
-        water.translation.y = spawn_point.y - 170.;
+        water.translation.y = spawn_point.translation.y - 170.;
     }
 }
 
@@ -144,6 +147,7 @@ fn spawn_world(
     mut water_settings: ResMut<WaterSettings>,
     q_player: Query<Entity, With<LogicalPlayer>>,
     fx: Res<ParticleEffects>,
+    levels: Res<Levels>,
 ) {
     if main_scene.is_spawned {
         return;
@@ -158,26 +162,18 @@ fn spawn_world(
         main_scene.is_spawned = true;
     }
 
+    let level = levels.get(current_level.get());
+
     let skybox_handle = main_scene.skybox(current_level.get());
     for entity in &q_camera {
         cmd.entity(entity).remove::<Skybox>().insert(Skybox {
             image: skybox_handle.clone(),
-            brightness: match current_level.get().get() {
-                1 => 30000.,
-                2 => 50000.,
-                3 => 50000.,
-                _ => 10000.,
-            },
+            brightness: level.skybox_brightness,
             ..default()
         });
     }
 
-    water_settings.deep_color = match current_level.get().get() {
-        1 => Resurrect64::DEEP_PURPLE,
-        2 => Resurrect64::DARK_CYAN,
-        3 => Resurrect64::DARK_RED_1,
-        _ => Resurrect64::DARK_CYAN,
-    };
+    water_settings.deep_color = level.water_deep_color;
 
     for player in &q_player {
         cmd.entity(player).with_child((
@@ -197,16 +193,14 @@ fn spawn_level(
     mut current_level: ResMut<CurrentLevel>,
     mut main_scene: ResMut<MainScene>,
     mut er: MessageReader<SpawnLevel>,
-    mut q_player: Query<&mut Transform, With<LogicalPlayer>>,
+    mut ew: MessageWriter<Respawn<LogicalPlayer>>,
 
     mut timer: ResMut<LevelDuration>,
 ) {
-    let spawn_point = SPAWN_POINT;
-
     let scene = scene.into_inner();
 
     for e in er.read() {
-        history.0.clear();
+        history.reset();
         timer.0.reset();
 
         current_level.0 = e.0;
@@ -214,8 +208,28 @@ fn spawn_level(
 
         cmd.entity(scene).despawn();
 
-        for mut transform in &mut q_player {
-            transform.translation = spawn_point;
+        ew.write(Respawn::placement(history.spawn));
+    }
+}
+
+fn spawn_points(
+    mut cmd: Commands,
+    main_scene: Res<MainScene>,
+    mut history: ResMut<History>,
+    q_spawn_point: Query<(Entity, &SpawnPoint, &GlobalTransform), Without<Ready>>,
+    mut ew: MessageWriter<Respawn<LogicalPlayer>>,
+) {
+    if !main_scene.is_spawned {
+        return;
+    }
+
+    for (entity, spawn_point, gtf) in &q_spawn_point {
+        cmd.entity(entity).insert(Ready);
+
+        history.spawn = spawn_point.spawn_transform(gtf);
+
+        if history.empty() {
+            ew.write(Respawn::placement(history.spawn));
         }
     }
 }